@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+/// Busca varios textos fijos a la vez con un autómata de Aho-Corasick.
+///
+/// Los textos forman un árbol de prefijos; cada estado guarda a qué estado volver cuando el
+/// siguiente byte no continúa ningún texto, así la entrada se recorre una sola vez sin importar
+/// cuántos textos se busquen.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    /// Las transiciones de cada estado, ordenadas por byte.
+    transitions: Vec<Vec<(u8, usize)>>,
+    /// El estado del sufijo más largo que también es prefijo de algún texto.
+    fail: Vec<usize>,
+    /// Los textos que terminan en cada estado, incluidos los que terminan en sus sufijos.
+    outputs: Vec<Vec<usize>>,
+    /// El largo, en bytes, de cada texto.
+    lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+    /// Arma el autómata que busca todos los textos.
+    ///
+    /// # Arguments
+    ///
+    /// * `needles` - Los textos a buscar, en bytes.
+    pub fn new(needles: &[Vec<u8>]) -> AhoCorasick {
+        let mut automaton = AhoCorasick {
+            transitions: vec![Vec::new()],
+            fail: vec![0],
+            outputs: vec![Vec::new()],
+            lengths: needles.iter().map(Vec::len).collect(),
+        };
+        for (index, needle) in needles.iter().enumerate() {
+            automaton.insert(index, needle);
+        }
+        automaton.link();
+        automaton
+    }
+
+    /// Devuelve el largo, en bytes, del texto más largo.
+    pub fn max_len(&self) -> usize {
+        self.lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Recorre las apariciones de los textos que empiezan en `start` o después, en el orden en
+    /// que terminan.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - Los bytes en los que se busca.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    /// * `found` - Recibe el inicio y el fin de cada aparición; si devuelve `false` se deja de
+    ///   buscar.
+    pub fn each_match(
+        &self,
+        haystack: &[u8],
+        start: usize,
+        found: &mut dyn FnMut(usize, usize) -> bool,
+    ) {
+        if start > haystack.len() {
+            return;
+        }
+        if !self.report(0, haystack, start, found) {
+            return;
+        }
+
+        let mut state = 0;
+        for (pos, &byte) in haystack.iter().enumerate().skip(start) {
+            state = self.next_state(state, byte);
+            if !self.report(state, haystack, pos + 1, found) {
+                return;
+            }
+        }
+    }
+
+    /// Agrega un texto al árbol de prefijos.
+    fn insert(&mut self, index: usize, needle: &[u8]) {
+        let mut state = 0;
+        for &byte in needle {
+            state = match self.transition(state, byte) {
+                Some(next) => next,
+                None => {
+                    let next = self.transitions.len();
+                    self.transitions.push(Vec::new());
+                    self.fail.push(0);
+                    self.outputs.push(Vec::new());
+                    let transitions = &mut self.transitions[state];
+                    let at = transitions.partition_point(|&(other, _)| other < byte);
+                    transitions.insert(at, (byte, next));
+                    next
+                }
+            };
+        }
+        self.outputs[state].push(index);
+    }
+
+    /// Calcula, recorriendo el árbol a lo ancho, el estado de retroceso de cada estado y le
+    /// suma los textos que terminan en él.
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.transitions[0].iter().map(|&(_, s)| s).collect();
+        for &state in &queue {
+            let inherited = self.outputs[0].clone();
+            self.outputs[state].extend(inherited);
+        }
+        while let Some(state) = queue.pop_front() {
+            for (byte, next) in self.transitions[state].clone() {
+                let mut fallback = self.fail[state];
+                let fail = loop {
+                    match self.transition(fallback, byte) {
+                        Some(target) => break target,
+                        None if fallback == 0 => break 0,
+                        None => fallback = self.fail[fallback],
+                    }
+                };
+                self.fail[next] = fail;
+                let inherited = self.outputs[fail].clone();
+                self.outputs[next].extend(inherited);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    /// Devuelve el estado al que se llega desde `state` con `byte`, si hay una transición.
+    fn transition(&self, state: usize, byte: u8) -> Option<usize> {
+        let transitions = &self.transitions[state];
+        transitions
+            .binary_search_by_key(&byte, |&(other, _)| other)
+            .ok()
+            .map(|at| transitions[at].1)
+    }
+
+    /// Avanza el autómata con `byte`, retrocediendo hasta encontrar una transición.
+    fn next_state(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(next) = self.transition(state, byte) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.fail[state];
+        }
+    }
+
+    /// Informa los textos que terminan en `end` al llegar a `state`. Los textos vacíos sólo se
+    /// informan al comienzo de un caracter.
+    fn report(
+        &self,
+        state: usize,
+        haystack: &[u8],
+        end: usize,
+        found: &mut dyn FnMut(usize, usize) -> bool,
+    ) -> bool {
+        for &index in &self.outputs[state] {
+            let len = self.lengths[index];
+            if len == 0 && !AhoCorasick::starts_char(haystack, end) {
+                continue;
+            }
+            if !found(end - len, end) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Indica si `pos` es el comienzo de un caracter o el final de los bytes.
+    fn starts_char(haystack: &[u8], pos: usize) -> bool {
+        haystack
+            .get(pos)
+            .is_none_or(|&byte| !(0x80..0xc0).contains(&byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(needles: &[&str], haystack: &str, start: usize) -> Vec<(usize, usize)> {
+        let needles: Vec<Vec<u8>> = needles.iter().map(|n| n.as_bytes().to_vec()).collect();
+        let mut found = Vec::new();
+        AhoCorasick::new(&needles).each_match(haystack.as_bytes(), start, &mut |s, e| {
+            found.push((s, e));
+            true
+        });
+        found
+    }
+
+    #[test]
+    fn test_each_match() {
+        assert_eq!(
+            matches(&["he", "she", "his", "hers"], "ushers", 0),
+            vec![(1, 4), (2, 4), (2, 6)]
+        );
+        assert_eq!(
+            matches(&["a.b", "[0]"], "x[0] a.b", 0),
+            vec![(1, 4), (5, 8)]
+        );
+        assert_eq!(matches(&["a.b", "[0]"], "x[0] a.b", 2), vec![(5, 8)]);
+        assert_eq!(matches(&["kiwi"], "banana", 0), vec![]);
+        assert_eq!(matches(&["ñu", "u"], "ñu", 0), vec![(0, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn test_empty_needle() {
+        assert_eq!(
+            matches(&["", "b"], "añb", 0),
+            vec![(0, 0), (1, 1), (3, 3), (3, 4), (4, 4)]
+        );
+    }
+
+    #[test]
+    fn test_max_len() {
+        let needles = vec![b"ab".to_vec(), b"abcd".to_vec()];
+        assert_eq!(AhoCorasick::new(&needles).max_len(), 4);
+        assert_eq!(AhoCorasick::new(&[]).max_len(), 0);
+    }
+}
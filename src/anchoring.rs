@@ -96,7 +96,10 @@ impl Anchoring {
             .map(|step| match &step.val {
                 RegexValue::Literal(c) => c.to_string(),
                 RegexValue::Wildcard => ".".to_string(),
-                RegexValue::Clase(_) => "".to_string(),
+                RegexValue::Clase(_)
+                | RegexValue::Grupo(_)
+                | RegexValue::Inicio { .. }
+                | RegexValue::Fin { .. } => "".to_string(),
             })
             .collect()
     }
@@ -0,0 +1,11 @@
+/// Representa qué hacer con los archivos binarios, según `--binary-files`, `-a` e `-I`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BinaryFiles {
+    /// Sólo informa si el archivo tiene alguna coincidencia.
+    #[default]
+    Binary,
+    /// Busca en el archivo como si fuera texto.
+    Text,
+    /// Supone que el archivo no tiene coincidencias.
+    WithoutMatch,
+}
@@ -1,5 +1,5 @@
 use crate::{
-    character_class::CharacterClass, grep_error::GrepError, regex_rep::RegexRep,
+    character_class::CharacterClass, escape, grep_error::GrepError, regex_rep::RegexRep,
     regex_step::RegexStep, regex_value::RegexValue,
 };
 use std::str::Chars;
@@ -77,6 +77,9 @@ impl BracketExpression {
 
     /// Lee y procesa una expresión entre corchetes `[...]` y devuelve su representación como `RegexValue`.
     ///
+    /// Dentro de los corchetes se aceptan las mismas secuencias de escape que en el resto del
+    /// patrón, como `\t` o `\x{263A}`, y `\]` representa un `]`.
+    ///
     /// # Arguments
     ///
     /// * `chars_iter` - Un iterador de caracteres que representa la expresión entre corchetes.
@@ -96,11 +99,12 @@ impl BracketExpression {
             negated = true;
         }
 
-        for inner_c in chars_iter.by_ref() {
-            if inner_c == ']' {
-                break;
+        while let Some(inner_c) = chars_iter.next() {
+            match inner_c {
+                ']' => break,
+                '\\' => characters.push(escape::read_escape(chars_iter)?),
+                _ => characters.push(inner_c),
             }
-            characters.push(inner_c);
         }
 
         let clase = if characters.is_empty() {
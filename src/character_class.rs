@@ -44,6 +44,38 @@ impl CharacterClass {
         }
     }
 
+    /// Devuelve la clase que acepta además las otras mayúsculas o minúsculas de sus caracteres,
+    /// para `(?i)`.
+    pub fn ignoring_case(self) -> CharacterClass {
+        match self {
+            CharacterClass::Lower | CharacterClass::Upper => CharacterClass::Alpha,
+            CharacterClass::Custom(chars, negado) => {
+                let chars = chars
+                    .iter()
+                    .flat_map(|&caracter| CharacterClass::case_variants(caracter))
+                    .collect();
+                CharacterClass::Custom(chars, negado)
+            }
+            clase => clase,
+        }
+    }
+
+    /// Devuelve el caracter junto con su versión en minúscula y en mayúscula, si las tiene y
+    /// son un solo caracter.
+    pub fn case_variants(caracter: char) -> Vec<char> {
+        let mut variants = vec![caracter];
+        let lower: Vec<char> = caracter.to_lowercase().collect();
+        let upper: Vec<char> = caracter.to_uppercase().collect();
+        for variant in [lower, upper] {
+            if let [other] = variant[..] {
+                if !variants.contains(&other) {
+                    variants.push(other);
+                }
+            }
+        }
+        variants
+    }
+
     /// Lee y procesa una clase de caracteres y devuelve su representación como `RegexValue`.
     ///
     /// # Arguments
@@ -156,4 +188,15 @@ mod tests {
         let clase_negada = CharacterClass::Custom(vec!['m', 'a', 't'], true);
         assert_eq!(clase_negada.valid_character('t'), false);
     }
+
+    #[test]
+    fn test_ignoring_case() {
+        assert_eq!(CharacterClass::Lower.ignoring_case(), CharacterClass::Alpha);
+        assert_eq!(CharacterClass::Digit.ignoring_case(), CharacterClass::Digit);
+        let clase = CharacterClass::Custom(vec!['a', 'Ñ', '1'], true).ignoring_case();
+        assert!(!clase.valid_character('A'));
+        assert!(!clase.valid_character('ñ'));
+        assert!(clase.valid_character('b'));
+        assert_eq!(CharacterClass::case_variants('ß'), vec!['ß']);
+    }
 }
@@ -0,0 +1,8 @@
+/// Representa cuándo colorear la salida, según `--color`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    #[default]
+    Never,
+}
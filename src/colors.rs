@@ -0,0 +1,125 @@
+/// Guarda las secuencias SGR con las que se colorea cada parte de la salida, con el mismo
+/// formato que la variable de entorno `GREP_COLORS` de GNU grep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    pub selected_match: String,
+    pub context_match: String,
+    pub selected_line: String,
+    pub context_line: String,
+    pub filename: String,
+    pub line_number: String,
+    pub byte_offset: String,
+    pub separator: String,
+    pub erase_line: bool,
+}
+
+impl Default for Colors {
+    /// Crea los colores por defecto de GNU grep: `ms=01;31:mc=01;31:sl=:cx=:fn=35:ln=32:bn=32:se=36`.
+    fn default() -> Self {
+        Colors {
+            selected_match: "01;31".to_string(),
+            context_match: "01;31".to_string(),
+            selected_line: String::new(),
+            context_line: String::new(),
+            filename: "35".to_string(),
+            line_number: "32".to_string(),
+            byte_offset: "32".to_string(),
+            separator: "36".to_string(),
+            erase_line: true,
+        }
+    }
+}
+
+impl Colors {
+    /// Crea los colores a partir del valor de `GREP_COLORS`, partiendo de los colores por defecto.
+    ///
+    /// Las capacidades se separan con `:`; las que no se reconocen se ignoran.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - El valor de la variable, por ejemplo `ms=01;32:fn=34:ne`.
+    pub fn parse(spec: &str) -> Colors {
+        let mut colors = Colors::default();
+
+        for capability in spec.split(':') {
+            match capability.split_once('=') {
+                Some((name, sgr)) => {
+                    let sgr = sgr.to_string();
+                    match name {
+                        "mt" => {
+                            colors.selected_match = sgr.clone();
+                            colors.context_match = sgr;
+                        }
+                        "ms" => colors.selected_match = sgr,
+                        "mc" => colors.context_match = sgr,
+                        "sl" => colors.selected_line = sgr,
+                        "cx" => colors.context_line = sgr,
+                        "fn" => colors.filename = sgr,
+                        "ln" => colors.line_number = sgr,
+                        "bn" => colors.byte_offset = sgr,
+                        "se" => colors.separator = sgr,
+                        _ => {}
+                    }
+                }
+                None => {
+                    if capability == "ne" {
+                        colors.erase_line = false;
+                    }
+                }
+            }
+        }
+
+        colors
+    }
+
+    /// Envuelve el texto entre la secuencia SGR indicada y la que restablece el color.
+    ///
+    /// # Arguments
+    ///
+    /// * `sgr` - Los parámetros de la secuencia, como `01;31`. Si está vacío no se colorea.
+    /// * `text` - El texto a colorear, que no necesita ser UTF-8 válido.
+    pub fn paint(&self, sgr: &str, text: &[u8]) -> Vec<u8> {
+        if sgr.is_empty() || text.is_empty() {
+            return text.to_vec();
+        }
+        let erase = if self.erase_line { "\x1b[K" } else { "" };
+        let mut painted = format!("\x1b[{}m{}", sgr, erase).into_bytes();
+        painted.extend_from_slice(text);
+        painted.extend_from_slice(format!("\x1b[m{}", erase).as_bytes());
+        painted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint() {
+        let colors = Colors::default();
+        assert_eq!(
+            colors.paint("01;31", b"hola"),
+            b"\x1b[01;31m\x1b[Khola\x1b[m\x1b[K"
+        );
+        assert_eq!(colors.paint("", b"hola"), b"hola");
+    }
+
+    #[test]
+    fn test_parse() {
+        let colors = Colors::parse("ms=04;32:fn=34:se=:ne:xx=1");
+        assert_eq!(colors.selected_match, "04;32");
+        assert_eq!(colors.context_match, "01;31");
+        assert_eq!(colors.filename, "34");
+        assert_eq!(colors.separator, "");
+        assert_eq!(colors.line_number, "32");
+        assert!(!colors.erase_line);
+        assert_eq!(colors.paint("34", b"a"), b"\x1b[34ma\x1b[m");
+    }
+
+    #[test]
+    fn test_parse_mt() {
+        let colors = Colors::parse("mt=01;33");
+        assert_eq!(colors.selected_match, "01;33");
+        assert_eq!(colors.context_match, "01;33");
+    }
+}
@@ -0,0 +1,57 @@
+/// Representa la sintaxis de los patrones, elegida con `-G` o `-E`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    /// `-G`: expresiones regulares básicas, donde `\(`, `\)`, `\{`, `\}`, `\|`, `\+` y `\?`
+    /// son operadores y sin la `\` son literales.
+    Basic,
+    /// `-E`: expresiones regulares extendidas, la sintaxis por defecto.
+    #[default]
+    Extended,
+}
+
+impl Dialect {
+    /// Indica si un caracter es un operador de grupo, alternativa o repetición en este
+    /// dialecto, según esté escapado o no.
+    ///
+    /// El `*`, el `^` y el `$` son operadores sin escapar en los dos dialectos; en la sintaxis
+    /// básica el parser decide según dónde aparecen si son literales.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - El caracter.
+    /// * `escaped` - Si está precedido por una `\`.
+    pub fn is_operator(&self, c: char, escaped: bool) -> bool {
+        let operator = matches!(c, '(' | ')' | '{' | '}' | '|' | '+' | '?');
+        match self {
+            Dialect::Basic => operator && escaped,
+            Dialect::Extended => operator && !escaped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_operators() {
+        let extended = Dialect::Extended;
+        for c in ['(', ')', '{', '}', '|', '+', '?'] {
+            assert!(extended.is_operator(c, false));
+            assert!(!extended.is_operator(c, true));
+        }
+        assert!(!extended.is_operator('a', false));
+        assert!(!extended.is_operator('*', false));
+    }
+
+    #[test]
+    fn test_basic_operators() {
+        let basic = Dialect::Basic;
+        for c in ['(', ')', '{', '}', '|', '+', '?'] {
+            assert!(basic.is_operator(c, true));
+            assert!(!basic.is_operator(c, false));
+        }
+        assert!(!basic.is_operator('.', true));
+        assert!(!basic.is_operator('*', true));
+    }
+}
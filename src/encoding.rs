@@ -0,0 +1,124 @@
+use std::io::BufRead;
+
+use crate::grep_error::GrepError;
+
+/// Representa la codificación de las entradas, según `--encoding`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// Usa la marca de orden de bytes (BOM) si la hay; si no, supone UTF-8.
+    #[default]
+    Auto,
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    /// Interpreta el nombre de una codificación.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre, sin distinguir mayúsculas, como `utf16le` o `latin1`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la codificación, o `None` si el nombre no es conocido.
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Some(Encoding::Auto),
+            "utf8" | "utf-8" => Some(Encoding::Utf8),
+            "utf16le" | "utf-16le" => Some(Encoding::Utf16Le),
+            "utf16be" | "utf-16be" => Some(Encoding::Utf16Be),
+            "latin1" | "latin-1" | "iso-8859-1" => Some(Encoding::Latin1),
+            _ => None,
+        }
+    }
+
+    /// Decide la codificación de una entrada mirando si empieza con una marca de orden de bytes,
+    /// y consume la marca del lector.
+    ///
+    /// Con `Auto` la marca elige la codificación; con una codificación explícita sólo se
+    /// consume la marca que le corresponde.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - El lector sobre la entrada, todavía sin consumir.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la codificación con la que hay que leer la entrada, que nunca es `Auto`.
+    ///
+    /// Si hay un error al leer, devuelve un error de tipo `GrepError`.
+    pub fn detect<R: BufRead>(self, reader: &mut R) -> Result<Encoding, GrepError> {
+        let start = reader.fill_buf().map_err(|_| GrepError::ErrArchivo)?;
+        let bom = if start.starts_with(&[0xef, 0xbb, 0xbf]) {
+            Some((Encoding::Utf8, 3))
+        } else if start.starts_with(&[0xff, 0xfe]) {
+            Some((Encoding::Utf16Le, 2))
+        } else if start.starts_with(&[0xfe, 0xff]) {
+            Some((Encoding::Utf16Be, 2))
+        } else {
+            None
+        };
+
+        let encoding = match (self, bom) {
+            (Encoding::Auto, None) => Encoding::Utf8,
+            (Encoding::Auto, Some((found, _))) => found,
+            (requested, _) => requested,
+        };
+        if let Some((found, size)) = bom {
+            if found == encoding {
+                reader.consume(size);
+            }
+        }
+        Ok(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Encoding::from_name("auto"), Some(Encoding::Auto));
+        assert_eq!(Encoding::from_name("UTF-8"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::from_name("utf16le"), Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::from_name("utf-16be"), Some(Encoding::Utf16Be));
+        assert_eq!(Encoding::from_name("ISO-8859-1"), Some(Encoding::Latin1));
+        assert_eq!(Encoding::from_name("ebcdic"), None);
+    }
+
+    #[test]
+    fn test_detect_bom() -> Result<(), GrepError> {
+        let mut reader: &[u8] = b"\xff\xfea\x00";
+        assert_eq!(Encoding::Auto.detect(&mut reader)?, Encoding::Utf16Le);
+        assert_eq!(reader, b"a\x00");
+
+        let mut reader: &[u8] = b"\xfe\xff\x00a";
+        assert_eq!(Encoding::Auto.detect(&mut reader)?, Encoding::Utf16Be);
+        assert_eq!(reader, b"\x00a");
+
+        let mut reader: &[u8] = b"\xef\xbb\xbfhola";
+        assert_eq!(Encoding::Auto.detect(&mut reader)?, Encoding::Utf8);
+        assert_eq!(reader, b"hola");
+
+        let mut reader: &[u8] = b"hola";
+        assert_eq!(Encoding::Auto.detect(&mut reader)?, Encoding::Utf8);
+        assert_eq!(reader, b"hola");
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_explicit() -> Result<(), GrepError> {
+        let mut reader: &[u8] = b"\xff\xfea\x00";
+        assert_eq!(Encoding::Latin1.detect(&mut reader)?, Encoding::Latin1);
+        assert_eq!(reader, b"\xff\xfea\x00");
+
+        let mut reader: &[u8] = b"\xff\xfea\x00";
+        assert_eq!(Encoding::Utf16Le.detect(&mut reader)?, Encoding::Utf16Le);
+        assert_eq!(reader, b"a\x00");
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Interpreta las secuencias de escape de los patrones, dentro y fuera de los corchetes.
+//!
+//! `\t`, `\n`, `\r`, `\f`, `\v` y `\0` representan los caracteres de control; `\xHH`,
+//! `\x{HHHH}` y `\u{HHHH}` un caracter por su código en hexadecimal. Cualquier otro caracter
+//! escapado se representa a sí mismo, como `\.` o `\\`.
+
+use std::str::Chars;
+
+use crate::grep_error::GrepError;
+
+/// Lee una secuencia de escape, justo después de la `\`.
+///
+/// # Arguments
+///
+/// * `chars_iter` - El iterador del patrón, posicionado después de la `\`.
+///
+/// # Returns
+///
+/// Devuelve el caracter que representa la secuencia.
+///
+/// Si el patrón termina en `\`, o el código no es hexadecimal o no es un caracter válido,
+/// devuelve un error de tipo `GrepError`.
+pub fn read_escape(chars_iter: &mut Chars) -> Result<char, GrepError> {
+    let c = chars_iter.next().ok_or(GrepError::Err)?;
+    let escaped = match c {
+        't' => '\t',
+        'n' => '\n',
+        'r' => '\r',
+        'f' => '\u{0c}',
+        'v' => '\u{0b}',
+        '0' => '\0',
+        'x' if chars_iter.clone().next() == Some('{') => read_braced_code(chars_iter)?,
+        'x' => {
+            let digits: String = chars_iter.by_ref().take(2).collect();
+            if digits.len() != 2 {
+                return Err(GrepError::Err);
+            }
+            code_point(&digits)?
+        }
+        'u' => read_braced_code(chars_iter)?,
+        other => other,
+    };
+    Ok(escaped)
+}
+
+/// Lee un código en hexadecimal entre llaves, como el de `\x{263A}`.
+fn read_braced_code(chars_iter: &mut Chars) -> Result<char, GrepError> {
+    if chars_iter.next() != Some('{') {
+        return Err(GrepError::Err);
+    }
+    let mut digits = String::new();
+    for c in chars_iter.by_ref() {
+        if c == '}' {
+            return code_point(&digits);
+        }
+        digits.push(c);
+    }
+    Err(GrepError::Err)
+}
+
+/// Devuelve el caracter con el código dado en hexadecimal.
+fn code_point(digits: &str) -> Result<char, GrepError> {
+    if digits.is_empty() || digits.len() > 6 {
+        return Err(GrepError::Err);
+    }
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(GrepError::Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escape(pattern: &str) -> Result<(char, String), GrepError> {
+        let mut chars_iter = pattern.chars();
+        let c = read_escape(&mut chars_iter)?;
+        Ok((c, chars_iter.collect()))
+    }
+
+    #[test]
+    fn test_control() -> Result<(), GrepError> {
+        assert_eq!(escape("tx")?, ('\t', "x".to_string()));
+        assert_eq!(escape("n")?.0, '\n');
+        assert_eq!(escape("r")?.0, '\r');
+        assert_eq!(escape("f")?.0, '\u{0c}');
+        assert_eq!(escape("v")?.0, '\u{0b}');
+        assert_eq!(escape("0")?.0, '\0');
+        assert_eq!(escape(".")?.0, '.');
+        assert_eq!(escape("\\")?.0, '\\');
+        Ok(())
+    }
+
+    #[test]
+    fn test_code_points() -> Result<(), GrepError> {
+        assert_eq!(escape("x41B")?, ('A', "B".to_string()));
+        assert_eq!(escape("xe9")?.0, 'é');
+        assert_eq!(escape("x{263A}")?.0, '☺');
+        assert_eq!(escape("u{1F34C}z")?, ('\u{1f34c}', "z".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(escape("").is_err());
+        assert!(escape("x4").is_err());
+        assert!(escape("xZZ").is_err());
+        assert!(escape("x{12").is_err());
+        assert!(escape("u{D800}").is_err());
+        assert!(escape("u{110000}").is_err());
+        assert!(escape("u263A").is_err());
+    }
+}
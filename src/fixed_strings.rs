@@ -0,0 +1,212 @@
+use crate::{aho_corasick::AhoCorasick, horspool::Horspool, utf8};
+
+/// Busca textos fijos, sin interpretar metacaracteres, según `-F`.
+///
+/// Un solo texto se busca con Boyer-Moore-Horspool y varios con un autómata de Aho-Corasick.
+/// Con `-i` se comparan los textos y la línea pasados a minúscula.
+#[derive(Debug, Clone)]
+pub struct FixedStrings {
+    finder: Finder,
+    ignore_case: bool,
+}
+
+/// El algoritmo con el que se buscan los textos.
+#[derive(Debug, Clone)]
+enum Finder {
+    Single(Horspool),
+    Many(AhoCorasick),
+}
+
+impl FixedStrings {
+    /// Prepara la búsqueda de los textos.
+    ///
+    /// # Arguments
+    ///
+    /// * `needles` - Los textos a buscar; una línea coincide si contiene alguno.
+    /// * `ignore_case` - Si no se distinguen mayúsculas de minúsculas.
+    pub fn new(needles: &[String], ignore_case: bool) -> FixedStrings {
+        let needles: Vec<Vec<u8>> = needles
+            .iter()
+            .map(|needle| match ignore_case {
+                true => FixedStrings::fold(needle.as_bytes()).0,
+                false => needle.as_bytes().to_vec(),
+            })
+            .collect();
+        let finder = match needles.as_slice() {
+            [needle] => Finder::Single(Horspool::new(needle)),
+            _ => Finder::Many(AhoCorasick::new(&needles)),
+        };
+        FixedStrings {
+            finder,
+            ignore_case,
+        }
+    }
+
+    /// Busca la primera aparición aceptada que empieza en `start` o después.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Los bytes en los que se busca, que no necesitan ser UTF-8 válido.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    /// * `accept` - Recibe el inicio y el fin de una aparición e indica si se acepta.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin, en bytes, de la aparición aceptada que empieza más a la
+    /// izquierda; si empiezan en el mismo lugar, la más larga.
+    pub fn find_where_bytes(
+        &self,
+        value: &[u8],
+        start: usize,
+        accept: &dyn Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        if start > value.len() {
+            return None;
+        }
+        let folded;
+        let (haystack, offsets) = match self.ignore_case {
+            true => {
+                folded = FixedStrings::fold(&value[start..]);
+                (folded.0.as_slice(), Some(&folded.1))
+            }
+            false => (value, None),
+        };
+        let from = match offsets {
+            Some(_) => 0,
+            None => start,
+        };
+
+        let max_len = match &self.finder {
+            Finder::Single(horspool) => horspool.max_len(),
+            Finder::Many(aho_corasick) => aho_corasick.max_len(),
+        };
+        let mut best: Option<(usize, usize, usize)> = None;
+        let mut found = |begin: usize, end: usize| {
+            if best.is_some_and(|(first, _, _)| end > first + max_len) {
+                return false;
+            }
+            let (begin_original, end_original) = match offsets {
+                Some(offsets) => (start + offsets[begin], start + offsets[end]),
+                None => (begin, end),
+            };
+            let better = match best {
+                Some((first, _, last)) => begin < first || (begin == first && end_original > last),
+                None => true,
+            };
+            if better && accept(begin_original, end_original) {
+                best = Some((begin, begin_original, end_original));
+            }
+            true
+        };
+        match &self.finder {
+            Finder::Single(horspool) => horspool.each_match(haystack, from, &mut found),
+            Finder::Many(aho_corasick) => aho_corasick.each_match(haystack, from, &mut found),
+        }
+
+        best.map(|(_, begin, end)| (begin, end))
+    }
+
+    /// Pasa los bytes a minúscula de a un caracter.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve los bytes en minúscula y, para cada posición de ellos y la del final, la
+    /// posición correspondiente en los bytes originales. Los bytes que no son UTF-8 válido
+    /// quedan igual.
+    fn fold(bytes: &[u8]) -> (Vec<u8>, Vec<usize>) {
+        let mut folded = Vec::with_capacity(bytes.len());
+        let mut offsets = Vec::with_capacity(bytes.len() + 1);
+        let mut pos = 0;
+        while let Some((c, size)) = utf8::decode(&bytes[pos..]) {
+            let mut lower = c.to_lowercase();
+            let c = match (lower.next(), lower.next()) {
+                (Some(single), None) => single,
+                _ => c,
+            };
+            let mut buffer = [0; 4];
+            let encoded = match size == 1 && !bytes[pos].is_ascii() {
+                true => &bytes[pos..=pos],
+                false => c.encode_utf8(&mut buffer).as_bytes(),
+            };
+            for _ in encoded {
+                offsets.push(pos);
+            }
+            folded.extend_from_slice(encoded);
+            pos += size;
+        }
+        offsets.push(pos);
+        (folded, offsets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(
+        needles: &[&str],
+        ignore_case: bool,
+        value: &str,
+        start: usize,
+    ) -> Option<(usize, usize)> {
+        let needles: Vec<String> = needles.iter().map(|needle| needle.to_string()).collect();
+        FixedStrings::new(&needles, ignore_case).find_where_bytes(
+            value.as_bytes(),
+            start,
+            &|_, _| true,
+        )
+    }
+
+    #[test]
+    fn test_metacharacters_are_literal() {
+        assert_eq!(find(&["a.b[0]"], false, "axb0 a.b[0]", 0), Some((5, 11)));
+        assert_eq!(find(&["a.b[0]"], false, "axb0", 0), None);
+        assert_eq!(find(&["(?i)^$"], false, "x(?i)^$", 0), Some((1, 7)));
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        assert_eq!(
+            find(&["melon", "apple"], false, "apple melon", 0),
+            Some((0, 5))
+        );
+        assert_eq!(
+            find(&["melon", "apple"], false, "apple melon", 1),
+            Some((6, 11))
+        );
+        assert_eq!(
+            find(&["cd", "abcdef", "bc"], false, "xabcdef", 0),
+            Some((1, 7))
+        );
+        assert_eq!(find(&["b", "ab", "abc"], false, "xabc", 0), Some((1, 4)));
+        assert_eq!(find(&["", "a"], false, "ba", 0), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        assert_eq!(find(&["KIWI"], true, "un Kiwi", 0), Some((3, 7)));
+        assert_eq!(find(&["Ñandú", "x"], true, "el ÑANDÚ", 0), Some((3, 10)));
+        assert_eq!(find(&["kiwi"], false, "un Kiwi", 0), None);
+        assert_eq!(find(&["ß"], true, "STRAẞE", 0), Some((4, 7)));
+    }
+
+    #[test]
+    fn test_ignore_case_invalid_utf8() {
+        let needles = vec!["caf".to_string()];
+        let fixed = FixedStrings::new(&needles, true);
+        assert_eq!(
+            fixed.find_where_bytes(b"\xff CAF\xe9", 1, &|_, _| true),
+            Some((2, 5))
+        );
+    }
+
+    #[test]
+    fn test_accept() {
+        let needles = vec!["ab".to_string(), "abc".to_string()];
+        let fixed = FixedStrings::new(&needles, false);
+        let found = fixed.find_where_bytes(b"abc ab", 0, &|_, end| end != 3);
+        assert_eq!(found, Some((0, 2)));
+        let found = fixed.find_where_bytes(b"abc ab", 0, &|start, _| start > 0);
+        assert_eq!(found, Some((4, 6)));
+    }
+}
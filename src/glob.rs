@@ -0,0 +1,199 @@
+use crate::glob_token::GlobToken;
+
+/// Representa un patrón glob, como los de `--include` o los de un `.gitignore`.
+///
+/// Reconoce `?`, `*`, `[...]` (negado con `!` o `^`), `\` para escapar y `**` como un
+/// componente entero de la ruta. `*`, `?` y `[...]` no aceptan `/`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glob {
+    tokens: Vec<GlobToken>,
+}
+
+impl Glob {
+    /// Crea un `Glob` a partir de un patrón.
+    ///
+    /// Un `[` sin cerrar y un `\` al final se toman como caracteres literales.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - El patrón glob.
+    pub fn new(pattern: &str) -> Glob {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '*' => {
+                    let mut end = i;
+                    while end < chars.len() && chars[end] == '*' {
+                        end += 1;
+                    }
+                    let whole_component = end - i >= 2
+                        && (i == 0 || chars[i - 1] == '/')
+                        && (end == chars.len() || chars[end] == '/');
+                    if !whole_component {
+                        tokens.push(GlobToken::Star);
+                    } else if end < chars.len() {
+                        tokens.push(GlobToken::DirsPrefix);
+                        end += 1;
+                    } else {
+                        tokens.push(GlobToken::DoubleStar);
+                    }
+                    i = end;
+                }
+                '?' => {
+                    tokens.push(GlobToken::AnyChar);
+                    i += 1;
+                }
+                '[' => match Glob::read_class(&chars, i + 1) {
+                    Some((token, end)) => {
+                        tokens.push(token);
+                        i = end;
+                    }
+                    None => {
+                        tokens.push(GlobToken::Literal('['));
+                        i += 1;
+                    }
+                },
+                '\\' if i + 1 < chars.len() => {
+                    tokens.push(GlobToken::Literal(chars[i + 1]));
+                    i += 2;
+                }
+                c => {
+                    tokens.push(GlobToken::Literal(c));
+                    i += 1;
+                }
+            }
+        }
+
+        Glob { tokens }
+    }
+
+    /// Indica si el texto completo coincide con el patrón.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - El texto a comparar, por ejemplo un nombre de archivo o una ruta con `/`.
+    pub fn matches(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        Glob::match_tokens(&self.tokens, &chars)
+    }
+
+    /// Lee una clase `[...]` que empieza después del `[`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - Los caracteres del patrón.
+    /// * `start` - La posición siguiente al `[`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la clase y la posición siguiente al `]`, o `None` si la clase no se cierra.
+    fn read_class(chars: &[char], start: usize) -> Option<(GlobToken, usize)> {
+        let mut i = start;
+        let negated = matches!(chars.get(i), Some('!') | Some('^'));
+        if negated {
+            i += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let first = i;
+        loop {
+            let mut c = *chars.get(i)?;
+            if c == ']' && i > first {
+                return Some((GlobToken::Class { ranges, negated }, i + 1));
+            }
+            if c == '\\' {
+                i += 1;
+                c = *chars.get(i)?;
+            }
+            i += 1;
+            match (chars.get(i), chars.get(i + 1)) {
+                (Some('-'), Some(&to)) if to != ']' => {
+                    ranges.push((c, to));
+                    i += 2;
+                }
+                _ => ranges.push((c, c)),
+            }
+        }
+    }
+
+    /// Compara los caracteres restantes con las partes restantes del patrón, probando todas las
+    /// longitudes posibles para las partes que abarcan una cantidad variable de caracteres.
+    fn match_tokens(tokens: &[GlobToken], text: &[char]) -> bool {
+        let (token, rest) = match tokens.split_first() {
+            Some(split) => split,
+            None => return text.is_empty(),
+        };
+
+        match token {
+            GlobToken::Star => {
+                for i in 0..=text.len() {
+                    if Glob::match_tokens(rest, &text[i..]) {
+                        return true;
+                    }
+                    if i < text.len() && text[i] == '/' {
+                        return false;
+                    }
+                }
+                false
+            }
+            GlobToken::DoubleStar => (0..=text.len()).any(|i| Glob::match_tokens(rest, &text[i..])),
+            GlobToken::DirsPrefix => {
+                Glob::match_tokens(rest, text)
+                    || (0..text.len())
+                        .any(|i| text[i] == '/' && Glob::match_tokens(rest, &text[i + 1..]))
+            }
+            _ => match text.split_first() {
+                Some((c, tail)) => token.matches_char(*c) && Glob::match_tokens(rest, tail),
+                None => false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_and_wildcards() {
+        assert!(Glob::new("main.rs").matches("main.rs"));
+        assert!(!Glob::new("main.rs").matches("main.rss"));
+        assert!(Glob::new("*.rs").matches("main.rs"));
+        assert!(Glob::new("*.rs").matches(".rs"));
+        assert!(!Glob::new("*.rs").matches("src/main.rs"));
+        assert!(Glob::new("?.txt").matches("a.txt"));
+        assert!(!Glob::new("?.txt").matches("ab.txt"));
+    }
+
+    #[test]
+    fn test_class() {
+        let glob = Glob::new("[a-c]*.[!o]");
+        assert!(glob.matches("beta.c"));
+        assert!(!glob.matches("delta.c"));
+        assert!(!glob.matches("beta.o"));
+        assert!(Glob::new("[]x]").matches("]"));
+        assert!(Glob::new("a[b").matches("a[b"));
+        assert!(Glob::new("\\*").matches("*"));
+        assert!(!Glob::new("\\*").matches("a"));
+    }
+
+    #[test]
+    fn test_double_star() {
+        let glob = Glob::new("**/target");
+        assert!(glob.matches("target"));
+        assert!(glob.matches("a/b/target"));
+        assert!(!glob.matches("a/btarget"));
+
+        let glob = Glob::new("src/**/*.rs");
+        assert!(glob.matches("src/main.rs"));
+        assert!(glob.matches("src/a/b/main.rs"));
+        assert!(!glob.matches("lib/main.rs"));
+
+        assert!(Glob::new("abc/**").matches("abc/x/y"));
+        assert!(!Glob::new("abc/**").matches("abc"));
+        assert!(!Glob::new("a**b").matches("a/b"));
+    }
+}
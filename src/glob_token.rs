@@ -0,0 +1,60 @@
+/// Representa una parte de un patrón glob.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlobToken {
+    /// Un caracter que tiene que aparecer tal cual.
+    Literal(char),
+    /// `?`: cualquier caracter salvo `/`.
+    AnyChar,
+    /// `[...]`: un caracter, salvo `/`, que está (o no, si está negada) en alguno de los rangos.
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    /// `*`: cualquier cantidad de caracteres sin cruzar un `/`.
+    Star,
+    /// `**`: cualquier cantidad de caracteres, incluyendo `/`.
+    DoubleStar,
+    /// `**/`: nada, o cualquier cantidad de directorios enteros.
+    DirsPrefix,
+}
+
+impl GlobToken {
+    /// Indica si la parte del patrón, que tiene que ser de un solo caracter, acepta `c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `c` - El caracter a comparar.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `false` para las partes que abarcan una cantidad variable de caracteres.
+    pub fn matches_char(&self, c: char) -> bool {
+        match self {
+            GlobToken::Literal(literal) => *literal == c,
+            GlobToken::AnyChar => c != '/',
+            GlobToken::Class { ranges, negated } => {
+                c != '/' && ranges.iter().any(|(from, to)| *from <= c && c <= *to) != *negated
+            }
+            GlobToken::Star | GlobToken::DoubleStar | GlobToken::DirsPrefix => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_char() {
+        let class = GlobToken::Class {
+            ranges: vec![('a', 'c'), ('x', 'x')],
+            negated: false,
+        };
+        assert!(class.matches_char('b'));
+        assert!(class.matches_char('x'));
+        assert!(!class.matches_char('d'));
+        assert!(GlobToken::AnyChar.matches_char('z'));
+        assert!(!GlobToken::AnyChar.matches_char('/'));
+        assert!(!GlobToken::Star.matches_char('a'));
+    }
+}
@@ -5,13 +5,32 @@ use std::fmt;
 pub enum GrepError {
     Err,
     ErrArchivo,
+    ErrDirectorio,
+    ErrArgumentos,
+    ErrOpcion(String),
+    ErrArchivoPatrones(String),
+    ErrPatron(String, Box<GrepError>),
+    ErrRegex(String),
 }
 
 impl fmt::Display for GrepError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             GrepError::Err => write!(f, ""),
             GrepError::ErrArchivo => write!(f, "No existe el archivo o el directorio"),
+            GrepError::ErrDirectorio => write!(f, "Es un directorio"),
+            GrepError::ErrArgumentos => {
+                write!(f, "Uso: egrep_rustico [OPCION]... PATRON [ARCHIVO]...")
+            }
+            GrepError::ErrOpcion(opcion) => write!(f, "Opción inválida: '{}'", opcion),
+            GrepError::ErrArchivoPatrones(nombre) => {
+                write!(f, "{}: No existe el archivo o el directorio", nombre)
+            }
+            GrepError::ErrPatron(origen, causa) => match causa.as_ref() {
+                GrepError::Err => write!(f, "{}: Expresión regular inválida", origen),
+                causa => write!(f, "{}: {}", origen, causa),
+            },
+            GrepError::ErrRegex(motivo) => write!(f, "Expresión regular inválida: {}", motivo),
         }
     }
 }
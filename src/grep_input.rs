@@ -0,0 +1,149 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Seek, SeekFrom},
+};
+
+use crate::grep_error::GrepError;
+
+/// Representa una entrada en la que se va a buscar: la entrada estándar o un archivo.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrepInput {
+    Stdin,
+    Archivo(String),
+}
+
+impl GrepInput {
+    /// Crea una entrada a partir de un operando de la línea de comandos.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand` - El nombre del archivo, o `-` para la entrada estándar.
+    pub fn from_operand(operand: &str) -> GrepInput {
+        if operand == "-" {
+            GrepInput::Stdin
+        } else {
+            GrepInput::Archivo(operand.to_string())
+        }
+    }
+
+    /// Arma la lista de entradas a partir de los operandos.
+    ///
+    /// # Arguments
+    ///
+    /// * `operands` - Los archivos pasados por línea de comandos.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve una entrada por operando, o sólo la entrada estándar si no hay operandos.
+    pub fn from_operands(operands: &[String]) -> Vec<GrepInput> {
+        if operands.is_empty() {
+            return vec![GrepInput::Stdin];
+        }
+        operands
+            .iter()
+            .map(|operand| GrepInput::from_operand(operand))
+            .collect()
+    }
+
+    /// Devuelve el nombre con el que se muestra la entrada en la salida.
+    pub fn name(&self) -> &str {
+        match self {
+            GrepInput::Stdin => "(standard input)",
+            GrepInput::Archivo(nombre) => nombre,
+        }
+    }
+
+    /// Abre la entrada para leerla.
+    ///
+    /// La entrada estándar se lee a través de un duplicado de su descriptor, que comparte la
+    /// posición con el original, para poder devolver lo que se leyó de más con `unread`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve un lector con buffer sobre la entrada.
+    ///
+    /// Si hay un error al abrir el archivo, devuelve un error de tipo `GrepError`.
+    pub fn open(&self) -> Result<BufReader<File>, GrepError> {
+        let file = match self {
+            GrepInput::Stdin => GrepInput::stdin_file(),
+            GrepInput::Archivo(nombre) => File::open(nombre),
+        };
+        match file {
+            Ok(file) => Ok(BufReader::new(file)),
+            Err(_) => Err(GrepError::ErrArchivo),
+        }
+    }
+
+    /// Vuelve la posición de la entrada hasta justo después de lo último que se consumió del
+    /// lector, descartando lo que quedó en su buffer.
+    ///
+    /// Así, si se dejó de leer antes del final, quien siga leyendo la entrada estándar empieza en
+    /// la línea siguiente. Si la entrada no permite moverse, como un pipe, no hace nada.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - El lector devuelto por `open`.
+    pub fn unread(reader: &mut BufReader<File>) {
+        let pending = reader.buffer().len() as i64;
+        if pending > 0 {
+            let _ = reader.get_mut().seek(SeekFrom::Current(-pending));
+        }
+    }
+
+    /// Duplica el descriptor de la entrada estándar como un `File`.
+    #[cfg(unix)]
+    fn stdin_file() -> io::Result<File> {
+        use std::os::fd::AsFd;
+        Ok(File::from(io::stdin().as_fd().try_clone_to_owned()?))
+    }
+
+    /// Duplica el handle de la entrada estándar como un `File`.
+    #[cfg(windows)]
+    fn stdin_file() -> io::Result<File> {
+        use std::os::windows::io::AsHandle;
+        Ok(File::from(io::stdin().as_handle().try_clone_to_owned()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_operands() {
+        let operands = vec!["a.txt".to_string(), "-".to_string()];
+        assert_eq!(
+            GrepInput::from_operands(&operands),
+            vec![GrepInput::Archivo("a.txt".to_string()), GrepInput::Stdin]
+        );
+        assert_eq!(GrepInput::from_operands(&[]), vec![GrepInput::Stdin]);
+    }
+
+    #[test]
+    fn test_unread_restores_position() -> Result<(), GrepError> {
+        use std::io::{BufRead, Read};
+
+        let input = GrepInput::Archivo("src/frutas.txt".to_string());
+        let mut reader = input.open()?;
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|_| GrepError::ErrArchivo)?;
+        assert_eq!(line, "banana\n");
+
+        GrepInput::unread(&mut reader);
+        let mut rest = String::new();
+        reader
+            .get_mut()
+            .read_to_string(&mut rest)
+            .map_err(|_| GrepError::ErrArchivo)?;
+        assert!(rest.starts_with("apple\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_missing_file() {
+        let input = GrepInput::Archivo("no_existe.txt".to_string());
+        assert!(input.open().is_err());
+    }
+}
@@ -0,0 +1,714 @@
+use std::slice::Iter;
+
+use crate::{
+    binary_files::BinaryFiles, color_choice::ColorChoice, colors::Colors, dialect::Dialect,
+    encoding::Encoding, glob::Glob, grep_error::GrepError, output_mode::OutputMode,
+    recursion::Recursion,
+};
+
+/// Representa las opciones leídas de la línea de comandos.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GrepOptions {
+    pub patterns: Vec<String>,
+    pub pattern_files: Vec<String>,
+    pub files: Vec<String>,
+    pub with_filename: Option<bool>,
+    pub unique: bool,
+    pub invert_match: bool,
+    pub line_regexp: bool,
+    pub word_regexp: bool,
+    pub line_number: bool,
+    pub byte_offset: bool,
+    pub column: bool,
+    pub column_in_chars: bool,
+    pub before_context: usize,
+    pub after_context: usize,
+    pub output_mode: OutputMode,
+    pub max_count: Option<usize>,
+    pub only_matching: bool,
+    pub color: ColorChoice,
+    pub colors: Colors,
+    pub recursion: Recursion,
+    pub include: Vec<Glob>,
+    pub exclude: Vec<Glob>,
+    pub exclude_dir: Vec<Glob>,
+    pub max_depth: Option<usize>,
+    pub sort_paths: bool,
+    pub no_ignore: bool,
+    pub threads: usize,
+    pub unordered: bool,
+    pub binary_files: BinaryFiles,
+    pub encoding: Encoding,
+    pub null_data: bool,
+    pub null: bool,
+    pub record_start: Option<String>,
+    pub record_separator: Option<Vec<u8>>,
+    pub multiline: bool,
+    pub ignore_case: bool,
+    pub fixed_strings: bool,
+    pub dialect: Dialect,
+}
+
+impl GrepOptions {
+    /// Lee los argumentos de la línea de comandos y arma las opciones.
+    ///
+    /// Las opciones pueden aparecer antes o después de los operandos, salvo que se use `--`.
+    /// Si no se pasa ningún `-e` ni `-f`, el primer operando es el patrón y el resto son
+    /// archivos.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Los argumentos de la línea de comandos, incluyendo el nombre del programa.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve las opciones leídas, o un error de tipo `GrepError` si alguna opción es inválida
+    /// o falta el patrón.
+    pub fn parse(args: &[String]) -> Result<GrepOptions, GrepError> {
+        let mut options = GrepOptions::default();
+        let mut operands: Vec<String> = Vec::new();
+        let mut iter = args.get(1..).unwrap_or(&[]).iter();
+
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                operands.extend(iter.by_ref().cloned());
+                break;
+            }
+
+            if let Some(long) = arg.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                options.read_long(name, value, &mut iter)?;
+            } else if arg.len() > 1 && arg.starts_with('-') {
+                options.read_short(&arg[1..], &mut iter)?;
+            } else {
+                operands.push(arg.clone());
+            }
+        }
+
+        let mut operands = operands.into_iter();
+        if options.patterns.is_empty() && options.pattern_files.is_empty() {
+            match operands.next() {
+                Some(pattern) => options.patterns.push(pattern),
+                None => return Err(GrepError::ErrArgumentos),
+            }
+        }
+        options.files = operands.collect();
+
+        Ok(options)
+    }
+
+    /// Indica si hay que anteponer el nombre del archivo a cada línea impresa.
+    ///
+    /// # Arguments
+    ///
+    /// * `walked_dir` - Si el recorrido de las entradas ya entró en algún directorio.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el valor forzado con `-H`/`-h`, o `true` si se busca en más de una entrada o
+    /// se recorrió algún directorio.
+    pub fn show_filename(&self, walked_dir: bool) -> bool {
+        self.with_filename
+            .unwrap_or(self.files.len() > 1 || walked_dir)
+    }
+
+    /// Devuelve el byte que termina cada línea de la entrada y de la salida: NUL con `-z`, o
+    /// `\n` si no.
+    pub fn line_terminator(&self) -> u8 {
+        if self.null_data {
+            b'\0'
+        } else {
+            b'\n'
+        }
+    }
+
+    /// Procesa un grupo de opciones cortas, como `-H` o `-eabc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - Los caracteres que siguen al `-`.
+    /// * `iter` - El iterador de argumentos, por si la opción toma su valor del siguiente.
+    fn read_short(&mut self, flags: &str, iter: &mut Iter<String>) -> Result<(), GrepError> {
+        for (i, flag) in flags.char_indices() {
+            let rest = &flags[i + flag.len_utf8()..];
+            match flag {
+                'e' => {
+                    self.patterns
+                        .push(GrepOptions::short_value(flag, rest, iter)?);
+                    return Ok(());
+                }
+                'f' => {
+                    self.pattern_files
+                        .push(GrepOptions::short_value(flag, rest, iter)?);
+                    return Ok(());
+                }
+                'm' => {
+                    let value = GrepOptions::short_value(flag, rest, iter)?;
+                    self.max_count = Some(GrepOptions::number("-m", &value)?);
+                    return Ok(());
+                }
+                'j' => {
+                    let value = GrepOptions::short_value(flag, rest, iter)?;
+                    self.threads = GrepOptions::number("-j", &value)?;
+                    return Ok(());
+                }
+                'A' | 'B' | 'C' => {
+                    let value = GrepOptions::short_value(flag, rest, iter)?;
+                    let lines = GrepOptions::number(&format!("-{}", flag), &value)?;
+                    self.set_context(flag, lines);
+                    return Ok(());
+                }
+                'H' => self.with_filename = Some(true),
+                'h' => self.with_filename = Some(false),
+                'v' => self.invert_match = true,
+                'x' => self.line_regexp = true,
+                'w' => self.word_regexp = true,
+                'n' => self.line_number = true,
+                'b' => self.byte_offset = true,
+                'o' => self.only_matching = true,
+                'c' => self.output_mode = OutputMode::Count,
+                'l' => self.output_mode = OutputMode::FilesWithMatches,
+                'L' => self.output_mode = OutputMode::FilesWithoutMatch,
+                'q' => self.output_mode = OutputMode::Quiet,
+                'a' => self.binary_files = BinaryFiles::Text,
+                'I' => self.binary_files = BinaryFiles::WithoutMatch,
+                'z' => self.null_data = true,
+                'Z' => self.null = true,
+                'U' => self.multiline = true,
+                'i' => self.ignore_case = true,
+                'F' => self.fixed_strings = true,
+                'G' => self.set_dialect(Dialect::Basic),
+                'E' => self.set_dialect(Dialect::Extended),
+                'r' => self.recursion = Recursion::Recursive,
+                'R' => self.recursion = Recursion::FollowLinks,
+                _ => return Err(GrepError::ErrOpcion(format!("-{}", flag))),
+            }
+        }
+        Ok(())
+    }
+
+    /// Procesa una opción larga, como `--regexp=abc` o `--with-filename`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la opción sin los `--`.
+    /// * `value` - El valor que siguió al `=`, si lo hubo.
+    /// * `iter` - El iterador de argumentos, por si la opción toma su valor del siguiente.
+    fn read_long(
+        &mut self,
+        name: &str,
+        value: Option<String>,
+        iter: &mut Iter<String>,
+    ) -> Result<(), GrepError> {
+        match name {
+            "regexp" => self
+                .patterns
+                .push(GrepOptions::long_value(name, value, iter)?),
+            "file" => self
+                .pattern_files
+                .push(GrepOptions::long_value(name, value, iter)?),
+            "with-filename" => self.with_filename = Some(true),
+            "no-filename" => self.with_filename = Some(false),
+            "unique" => self.unique = true,
+            "invert-match" => self.invert_match = true,
+            "line-regexp" => self.line_regexp = true,
+            "word-regexp" => self.word_regexp = true,
+            "line-number" => self.line_number = true,
+            "byte-offset" => self.byte_offset = true,
+            "column" => self.column = true,
+            "only-matching" => self.only_matching = true,
+            "color" | "colour" => {
+                self.color = match value.as_deref() {
+                    None | Some("auto") | Some("tty") | Some("if-tty") => ColorChoice::Auto,
+                    Some("always") | Some("yes") | Some("force") => ColorChoice::Always,
+                    Some("never") | Some("no") | Some("none") => ColorChoice::Never,
+                    Some(other) => {
+                        return Err(GrepError::ErrOpcion(format!("--{}={}", name, other)))
+                    }
+                }
+            }
+            "count" => self.output_mode = OutputMode::Count,
+            "files-with-matches" => self.output_mode = OutputMode::FilesWithMatches,
+            "files-without-match" => self.output_mode = OutputMode::FilesWithoutMatch,
+            "quiet" | "silent" => self.output_mode = OutputMode::Quiet,
+            "max-count" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                self.max_count = Some(GrepOptions::number("--max-count", &value)?);
+            }
+            "after-context" | "before-context" | "context" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                let lines = GrepOptions::number(&format!("--{}", name), &value)?;
+                match name {
+                    "after-context" => self.set_context('A', lines),
+                    "before-context" => self.set_context('B', lines),
+                    _ => self.set_context('C', lines),
+                }
+            }
+            "recursive" => self.recursion = Recursion::Recursive,
+            "dereference-recursive" => self.recursion = Recursion::FollowLinks,
+            "include" | "exclude" | "exclude-dir" => {
+                let glob = Glob::new(&GrepOptions::long_value(name, value, iter)?);
+                match name {
+                    "include" => self.include.push(glob),
+                    "exclude" => self.exclude.push(glob),
+                    _ => self.exclude_dir.push(glob),
+                }
+            }
+            "no-ignore" => self.no_ignore = true,
+            "threads" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                self.threads = GrepOptions::number("--threads", &value)?;
+            }
+            "unordered" => self.unordered = true,
+            "text" => self.binary_files = BinaryFiles::Text,
+            "null-data" => self.null_data = true,
+            "null" => self.null = true,
+            "multiline" => self.multiline = true,
+            "ignore-case" => self.ignore_case = true,
+            "fixed-strings" => self.fixed_strings = true,
+            "basic-regexp" => self.set_dialect(Dialect::Basic),
+            "extended-regexp" => self.set_dialect(Dialect::Extended),
+            "binary-files" => {
+                self.binary_files = match GrepOptions::long_value(name, value, iter)?.as_str() {
+                    "binary" => BinaryFiles::Binary,
+                    "text" => BinaryFiles::Text,
+                    "without-match" => BinaryFiles::WithoutMatch,
+                    other => return Err(GrepError::ErrOpcion(format!("--{}={}", name, other))),
+                }
+            }
+            "encoding" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                self.encoding = Encoding::from_name(&value)
+                    .ok_or_else(|| GrepError::ErrOpcion(format!("--{}={}", name, value)))?;
+            }
+            "record-start" => {
+                self.record_start = Some(GrepOptions::long_value(name, value, iter)?);
+                self.record_separator = None;
+            }
+            "record-separator" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                if value.is_empty() {
+                    return Err(GrepError::ErrOpcion(format!("--{}=", name)));
+                }
+                self.record_separator = Some(GrepOptions::unescape(&value));
+                self.record_start = None;
+            }
+            "max-depth" => {
+                let value = GrepOptions::long_value(name, value, iter)?;
+                self.max_depth = Some(GrepOptions::number("--max-depth", &value)?);
+            }
+            "sort" => match GrepOptions::long_value(name, value, iter)?.as_str() {
+                "path" => self.sort_paths = true,
+                "none" => self.sort_paths = false,
+                other => return Err(GrepError::ErrOpcion(format!("--{}={}", name, other))),
+            },
+            "column-unit" => match GrepOptions::long_value(name, value, iter)?.as_str() {
+                "byte" => self.column_in_chars = false,
+                "char" => self.column_in_chars = true,
+                other => return Err(GrepError::ErrOpcion(format!("--{}={}", name, other))),
+            },
+            _ => return Err(GrepError::ErrOpcion(format!("--{}", name))),
+        }
+        Ok(())
+    }
+
+    /// Guarda la cantidad de líneas de contexto pedida con `-A`, `-B` o `-C`.
+    fn set_context(&mut self, flag: char, lines: usize) {
+        match flag {
+            'A' => self.after_context = lines,
+            'B' => self.before_context = lines,
+            _ => {
+                self.after_context = lines;
+                self.before_context = lines;
+            }
+        }
+    }
+
+    /// Guarda la sintaxis de los patrones pedida con `-G` o `-E`, que deja sin efecto un `-F`
+    /// anterior.
+    fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+        self.fixed_strings = false;
+    }
+
+    /// Interpreta el valor numérico de una opción.
+    ///
+    /// # Arguments
+    ///
+    /// * `option` - El nombre de la opción, para informarlo si el valor es inválido.
+    /// * `value` - El valor a interpretar.
+    fn number(option: &str, value: &str) -> Result<usize, GrepError> {
+        value
+            .parse::<usize>()
+            .map_err(|_| GrepError::ErrOpcion(format!("{} {}", option, value)))
+    }
+
+    /// Reemplaza las secuencias `\n`, `\t`, `\r`, `\0` y `\\` del valor de una opción por los
+    /// bytes que representan; el resto de los caracteres queda igual.
+    fn unescape(value: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            let unescaped = match c {
+                '\\' => match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('0') => '\0',
+                    Some('\\') => '\\',
+                    Some(other) => {
+                        bytes.push(b'\\');
+                        other
+                    }
+                    None => '\\',
+                },
+                c => c,
+            };
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(unescaped.encode_utf8(&mut buffer).as_bytes());
+        }
+        bytes
+    }
+
+    /// Devuelve el valor de una opción corta, pegado a ella o en el siguiente argumento.
+    fn short_value(flag: char, rest: &str, iter: &mut Iter<String>) -> Result<String, GrepError> {
+        if !rest.is_empty() {
+            return Ok(rest.to_string());
+        }
+        match iter.next() {
+            Some(value) => Ok(value.clone()),
+            None => Err(GrepError::ErrOpcion(format!("-{}", flag))),
+        }
+    }
+
+    /// Devuelve el valor de una opción larga, después del `=` o en el siguiente argumento.
+    fn long_value(
+        name: &str,
+        value: Option<String>,
+        iter: &mut Iter<String>,
+    ) -> Result<String, GrepError> {
+        match value.or_else(|| iter.next().cloned()) {
+            Some(value) => Ok(value),
+            None => Err(GrepError::ErrOpcion(format!("--{}", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        let mut args = vec!["egrep_rustico".to_string()];
+        args.extend(values.iter().map(|value| value.to_string()));
+        args
+    }
+
+    #[test]
+    fn test_pattern_and_files() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["abc", "uno.txt", "dos.txt"]))?;
+        assert_eq!(options.patterns, vec!["abc"]);
+        assert_eq!(options.files, vec!["uno.txt", "dos.txt"]);
+        assert!(options.show_filename(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_files_reads_stdin() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["abc"]))?;
+        assert!(options.files.is_empty());
+        assert!(!options.show_filename(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_e() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["-e", "abc", "-edef", "--regexp=ghi", "a.txt"]))?;
+        assert_eq!(options.patterns, vec!["abc", "def", "ghi"]);
+        assert_eq!(options.files, vec!["a.txt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_force_filename() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["abc", "-H", "a.txt"]))?;
+        assert!(options.show_filename(false));
+
+        let options = GrepOptions::parse(&args(&["-h", "abc", "a.txt", "b.txt"]))?;
+        assert!(!options.show_filename(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_dash_and_stdin() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["--", "-H", "-"]))?;
+        assert_eq!(options.patterns, vec!["-H"]);
+        assert_eq!(options.files, vec!["-"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique() -> Result<(), GrepError> {
+        assert!(!GrepOptions::parse(&args(&["abc"]))?.unique);
+        assert!(GrepOptions::parse(&args(&["--unique", "abc"]))?.unique);
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_flags() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["-vx", "--word-regexp", "abc"]))?;
+        assert!(options.invert_match);
+        assert!(options.line_regexp);
+        assert!(options.word_regexp);
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_flags() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["-nb", "--column", "--column-unit=char", "abc"]))?;
+        assert!(options.line_number);
+        assert!(options.byte_offset);
+        assert!(options.column);
+        assert!(options.column_in_chars);
+        assert!(GrepOptions::parse(&args(&["--column-unit=linea", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_context() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["-C", "2", "-A1", "abc"]))?;
+        assert_eq!(options.before_context, 2);
+        assert_eq!(options.after_context, 1);
+
+        let options = GrepOptions::parse(&args(&["--before-context=3", "abc"]))?;
+        assert_eq!(options.before_context, 3);
+        assert_eq!(options.after_context, 0);
+
+        assert!(GrepOptions::parse(&args(&["-A", "x", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_mode() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["abc"]))?;
+        assert_eq!(options.output_mode, OutputMode::Lines);
+        let options = GrepOptions::parse(&args(&["-c", "abc"]))?;
+        assert_eq!(options.output_mode, OutputMode::Count);
+        let options = GrepOptions::parse(&args(&["-lL", "abc"]))?;
+        assert_eq!(options.output_mode, OutputMode::FilesWithoutMatch);
+        let options = GrepOptions::parse(&args(&["--quiet", "abc"]))?;
+        assert_eq!(options.output_mode, OutputMode::Quiet);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_count() -> Result<(), GrepError> {
+        assert_eq!(GrepOptions::parse(&args(&["abc"]))?.max_count, None);
+        assert_eq!(
+            GrepOptions::parse(&args(&["-m10", "abc"]))?.max_count,
+            Some(10)
+        );
+        assert_eq!(
+            GrepOptions::parse(&args(&["--max-count", "0", "abc"]))?.max_count,
+            Some(0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_matching() -> Result<(), GrepError> {
+        assert!(GrepOptions::parse(&args(&["-on", "abc"]))?.only_matching);
+        assert!(GrepOptions::parse(&args(&["--only-matching", "abc"]))?.only_matching);
+        Ok(())
+    }
+
+    #[test]
+    fn test_color() -> Result<(), GrepError> {
+        assert_eq!(
+            GrepOptions::parse(&args(&["abc"]))?.color,
+            ColorChoice::Never
+        );
+        assert_eq!(
+            GrepOptions::parse(&args(&["--color", "abc"]))?.color,
+            ColorChoice::Auto
+        );
+        assert_eq!(
+            GrepOptions::parse(&args(&["--colour=always", "abc"]))?.color,
+            ColorChoice::Always
+        );
+        assert!(GrepOptions::parse(&args(&["--color=rojo", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursion() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&[
+            "-r",
+            "--include=*.rs",
+            "--exclude",
+            "main.rs",
+            "--exclude-dir=target",
+            "--max-depth=3",
+            "--sort=path",
+            "--no-ignore",
+            "abc",
+        ]))?;
+        assert_eq!(options.recursion, Recursion::Recursive);
+        assert_eq!(options.include, vec![Glob::new("*.rs")]);
+        assert_eq!(options.exclude, vec![Glob::new("main.rs")]);
+        assert_eq!(options.exclude_dir, vec![Glob::new("target")]);
+        assert_eq!(options.max_depth, Some(3));
+        assert!(options.sort_paths);
+        assert!(options.no_ignore);
+        assert!(options.show_filename(true));
+
+        let options = GrepOptions::parse(&args(&["-R", "abc", "Cargo.toml"]))?;
+        assert_eq!(options.recursion, Recursion::FollowLinks);
+        assert!(!options.show_filename(false));
+        assert!(GrepOptions::parse(&args(&["--sort=fecha", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_threads() -> Result<(), GrepError> {
+        assert_eq!(GrepOptions::parse(&args(&["abc"]))?.threads, 0);
+        assert_eq!(GrepOptions::parse(&args(&["-j4", "abc"]))?.threads, 4);
+        let options = GrepOptions::parse(&args(&["--threads", "2", "--unordered", "abc"]))?;
+        assert_eq!(options.threads, 2);
+        assert!(options.unordered);
+        assert!(GrepOptions::parse(&args(&["-j", "muchos", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_files() -> Result<(), GrepError> {
+        let parse = |values: &[&str]| GrepOptions::parse(&args(values));
+        assert_eq!(parse(&["abc"])?.binary_files, BinaryFiles::Binary);
+        assert_eq!(parse(&["-a", "abc"])?.binary_files, BinaryFiles::Text);
+        assert_eq!(parse(&["--text", "abc"])?.binary_files, BinaryFiles::Text);
+        assert_eq!(
+            parse(&["-I", "abc"])?.binary_files,
+            BinaryFiles::WithoutMatch
+        );
+        assert_eq!(
+            parse(&["--binary-files=without-match", "abc"])?.binary_files,
+            BinaryFiles::WithoutMatch
+        );
+        assert!(parse(&["--binary-files=imagen", "abc"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_null() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["abc"]))?;
+        assert_eq!(options.line_terminator(), b'\n');
+        assert!(!options.null);
+        let options = GrepOptions::parse(&args(&["-zZ", "abc"]))?;
+        assert_eq!(options.line_terminator(), b'\0');
+        assert!(options.null);
+        let options = GrepOptions::parse(&args(&["--null-data", "--null", "abc"]))?;
+        assert!(options.null_data && options.null);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["--record-separator", "\\n--\\n", "abc"]))?;
+        assert_eq!(options.record_separator, Some(b"\n--\n".to_vec()));
+        let options = GrepOptions::parse(&args(&["--record-separator=\\x;", "abc"]))?;
+        assert_eq!(options.record_separator, Some(b"\\x;".to_vec()));
+        let options = GrepOptions::parse(&args(&[
+            "--record-separator=;",
+            "--record-start=^[0-9]",
+            "abc",
+        ]))?;
+        assert_eq!(options.record_start, Some("^[0-9]".to_string()));
+        assert_eq!(options.record_separator, None);
+        assert!(GrepOptions::parse(&args(&["--record-separator=", "abc"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiline() -> Result<(), GrepError> {
+        assert!(!GrepOptions::parse(&args(&["abc"]))?.multiline);
+        assert!(GrepOptions::parse(&args(&["-U", "abc"]))?.multiline);
+        assert!(GrepOptions::parse(&args(&["--multiline", "abc"]))?.multiline);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_strings() -> Result<(), GrepError> {
+        assert!(!GrepOptions::parse(&args(&["a.b"]))?.fixed_strings);
+        assert!(GrepOptions::parse(&args(&["-F", "a.b"]))?.fixed_strings);
+        assert!(GrepOptions::parse(&args(&["--fixed-strings", "a.b"]))?.fixed_strings);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_files() -> Result<(), GrepError> {
+        let options = GrepOptions::parse(&args(&["-f", "a.txt", "--file=-", "b.txt"]))?;
+        assert!(options.patterns.is_empty());
+        assert_eq!(options.pattern_files, vec!["a.txt", "-"]);
+        assert_eq!(options.files, vec!["b.txt"]);
+
+        let options = GrepOptions::parse(&args(&["-fa.txt", "-e", "abc"]))?;
+        assert_eq!(options.pattern_files, vec!["a.txt"]);
+        assert_eq!(options.patterns, vec!["abc"]);
+        assert!(GrepOptions::parse(&args(&["-f"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dialect() -> Result<(), GrepError> {
+        assert_eq!(
+            GrepOptions::parse(&args(&["a"]))?.dialect,
+            Dialect::Extended
+        );
+        assert_eq!(
+            GrepOptions::parse(&args(&["-G", "a"]))?.dialect,
+            Dialect::Basic
+        );
+        let options = GrepOptions::parse(&args(&["--basic-regexp", "-E", "a"]))?;
+        assert_eq!(options.dialect, Dialect::Extended);
+
+        let options = GrepOptions::parse(&args(&["-F", "--basic-regexp", "a"]))?;
+        assert_eq!(options.dialect, Dialect::Basic);
+        assert!(!options.fixed_strings);
+        assert!(GrepOptions::parse(&args(&["-GF", "a"]))?.fixed_strings);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_case() -> Result<(), GrepError> {
+        assert!(!GrepOptions::parse(&args(&["abc"]))?.ignore_case);
+        assert!(GrepOptions::parse(&args(&["-i", "abc"]))?.ignore_case);
+        assert!(GrepOptions::parse(&args(&["--ignore-case", "abc"]))?.ignore_case);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoding() -> Result<(), GrepError> {
+        let parse = |values: &[&str]| GrepOptions::parse(&args(values));
+        assert_eq!(parse(&["abc"])?.encoding, Encoding::Auto);
+        assert_eq!(
+            parse(&["--encoding=utf16le", "abc"])?.encoding,
+            Encoding::Utf16Le
+        );
+        assert_eq!(
+            parse(&["--encoding", "latin1", "abc"])?.encoding,
+            Encoding::Latin1
+        );
+        assert!(parse(&["--encoding=ebcdic", "abc"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(GrepOptions::parse(&args(&[])).is_err());
+        assert!(GrepOptions::parse(&args(&["-k", "abc"])).is_err());
+        assert!(GrepOptions::parse(&args(&["abc", "-e"])).is_err());
+    }
+}
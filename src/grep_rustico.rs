@@ -1,15 +1,30 @@
 use std::{
-    fs::File,
-    io::{BufRead, BufReader, Lines},
+    collections::BTreeMap,
+    env,
+    io::{self, BufReader, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+    thread,
 };
 
-use crate::{grep_error::GrepError, regex::Regex};
+use crate::{
+    color_choice::ColorChoice, colors::Colors, encoding::Encoding, fixed_strings::FixedStrings,
+    grep_error::GrepError, grep_input::GrepInput, grep_options::GrepOptions,
+    input_walker::InputWalker, matcher::Matcher, output_mode::OutputMode, pattern::Pattern,
+    printer::Printer, recursion::Recursion, regex_flags::RegexFlags, search_output::SearchOutput,
+    searcher::Searcher, transcoder::Transcoder,
+};
 
 /// Representa un grep simple implementado en Rust.
 #[derive(Debug)]
 pub struct GrepRustico {
-    file: File,
-    regex_vec: Vec<Regex>,
+    options: GrepOptions,
+    inputs: Vec<GrepInput>,
+    matcher: Matcher,
+    searcher: Searcher,
 }
 
 impl GrepRustico {
@@ -23,140 +38,650 @@ impl GrepRustico {
     ///
     /// Devuelve un `GrepRustico` inicializado si los argumentos son válidos y no hay errores.
     ///
-    /// Si hay un error en los argumentos o al abrir el archivo, devuelve un error de tipo `GrepError`.
+    /// Si hay un error en los argumentos o en alguna expresión regular, devuelve un error de tipo `GrepError`.
     pub fn read_commands(args: Vec<String>) -> Result<GrepRustico, GrepError> {
-        if args.len() != 3 {
-            return Err(GrepError::Err);
+        let mut options = GrepOptions::parse(&args)?;
+        if let Ok(spec) = env::var("GREP_COLORS") {
+            options.colors = Colors::parse(&spec);
         }
 
-        let regex = &args[1];
-        let nombre_archivo = &args[2];
-
-        let file = GrepRustico::open_file(nombre_archivo)?;
-        let regex_vec = Regex::crear_regex(regex)?;
+        let patterns = Pattern::from_options(&options)?;
+        let matcher = if options.fixed_strings {
+            let needles: Vec<String> = patterns
+                .iter()
+                .map(|pattern| pattern.text.clone())
+                .collect();
+            let fixed_strings = FixedStrings::new(&needles, options.ignore_case);
+            Matcher::with_fixed_strings(fixed_strings, &options)
+        } else {
+            let flags = RegexFlags {
+                case_insensitive: options.ignore_case,
+                ..RegexFlags::default()
+            };
+            let set = Pattern::compile_set(&patterns, flags, options.dialect)?;
+            Matcher::new(set, &options)
+        };
+        let inputs = if options.files.is_empty() && options.recursion != Recursion::Off {
+            vec![GrepInput::Archivo(".".to_string())]
+        } else {
+            GrepInput::from_operands(&options.files)
+        };
+        let searcher = Searcher::new(&options)?;
 
-        Ok(GrepRustico { file, regex_vec })
+        Ok(GrepRustico {
+            options,
+            inputs,
+            matcher,
+            searcher,
+        })
     }
 
-    /// Ejecuta el grep en el archivo y devuelve un vector de las líneas que coinciden con las expresiones regulares.
-    ///
-    /// # Returns
+    /// Ejecuta el grep sobre todas las entradas e imprime las líneas que coinciden en la salida estándar.
     ///
-    /// Devuelve un vector de cadenas que representan las líneas que coinciden con las expresiones regulares.
+    /// Con `--color=auto` colorea la salida sólo si la salida estándar es una terminal.
     ///
-    /// Si hay un error al leer el archivo o al ejecutar el grep, devuelve un error de tipo `GrepError`.
-    pub fn run(&mut self) -> Result<Vec<String>, GrepError> {
-        let mut matches = Vec::new();
-        let cadena: Vec<String> = match self.read_words() {
-            Ok(cadena) => cadena,
-            Err(_err) => return Err(GrepError::ErrArchivo),
-        };
-
-        match self.filetr_and_run(&cadena) {
-            Ok(results) => {
-                for result in results {
-                    matches.push(result);
-                }
-            }
-            Err(_err) => return Err(GrepError::ErrArchivo),
-        };
-
-        self.print_matches(&matches);
-        Ok(matches)
-    }
-    /// Imprime las líneas que coinciden con las expresiones regulares.
+    /// # Returns
     ///
-    /// # Arguments
+    /// Devuelve `true` si alguna línea coincidió con las expresiones regulares.
     ///
-    /// * `matches` - Un vector de cadenas que representan las líneas que coinciden con las expresiones regulares.
-    fn print_matches(&self, matches: &Vec<String>) {
-        for linea in matches {
-            println!("{}", linea);
+    /// Si alguna entrada no se pudo leer, informa el error y sigue con las demás;
+    /// al terminar devuelve el último error de tipo `GrepError`.
+    pub fn run(&mut self) -> Result<bool, GrepError> {
+        let stdout = io::stdout();
+        if self.options.color == ColorChoice::Auto {
+            let dumb = env::var("TERM").is_ok_and(|term| term == "dumb");
+            self.options.color = if stdout.is_terminal() && !dumb {
+                ColorChoice::Always
+            } else {
+                ColorChoice::Never
+            };
         }
+        let mut out = stdout.lock();
+        self.run_with(&mut out)
     }
 
-    /// Abre un archivo dado su nombre.
+    /// Ejecuta el grep sobre todas las entradas e imprime las líneas que coinciden en `out`.
     ///
     /// # Arguments
     ///
-    /// * `nombre_archivo` - El nombre del archivo que se va a abrir.
+    /// * `out` - El destino donde se escriben las líneas que coinciden.
     ///
     /// # Returns
     ///
-    /// Devuelve un objeto `File` si el archivo se abre con éxito.
+    /// Devuelve `true` si alguna línea coincidió con las expresiones regulares.
     ///
-    /// Si hay un error al abrir el archivo, devuelve un error de tipo `GrepError`.
-    fn open_file(nombre_archivo: &str) -> Result<File, GrepError> {
-        match File::open(nombre_archivo) {
-            Ok(file) => Ok(file),
-            Err(_) => Err(GrepError::ErrArchivo),
+    /// Con `-r` o `-R` busca en los archivos de los directorios a medida que los recorre. Si hay
+    /// más de una entrada, busca en varias a la vez con los hilos pedidos con `-j`, o uno por
+    /// procesador.
+    ///
+    /// Si alguna entrada no se pudo leer, informa el error y sigue con las demás;
+    /// al terminar devuelve el último error de tipo `GrepError`. Con `-q` termina apenas
+    /// alguna línea coincide, y en ese caso no devuelve error.
+    pub fn run_with<W: Write>(&mut self, out: &mut W) -> Result<bool, GrepError> {
+        let threads = match self.options.threads {
+            0 => thread::available_parallelism().map_or(1, |threads| threads.get()),
+            threads => threads,
+        };
+        let single_input = self.inputs.len() == 1 && self.options.recursion == Recursion::Off;
+        if threads > 1 && !single_input {
+            self.run_parallel(out, threads)
+        } else {
+            self.run_sequential(out)
         }
     }
 
-    /// Lee todas las palabras del archivo y las devuelve como un vector de cadenas.
-    ///
-    /// # Returns
-    ///
-    /// Devuelve un vector de cadenas que representan todas las palabras del archivo.
-    ///
-    /// Si hay un error al leer el archivo, devuelve un error de tipo `GrepError`.
-    fn read_words(&self) -> Result<Vec<String>, GrepError> {
-        let lector_lineas: Lines<BufReader<&File>> = BufReader::new(&self.file).lines();
+    /// Busca en las entradas de a una, imprimiendo cada línea apenas se encuentra.
+    fn run_sequential<W: Write>(&mut self, out: &mut W) -> Result<bool, GrepError> {
+        let mut printer = Printer::new(out, &self.options);
+        let mut matched = false;
+        let mut error = None;
 
-        let cadenas = GrepRustico::read_file(lector_lineas)?;
+        let mut walker = InputWalker::new(self.inputs.clone(), &self.options);
+        while let Some(input) = walker.next() {
+            printer.set_with_filename(self.options.show_filename(walker.walked_dir()));
+            let input = match input {
+                Ok(input) => input,
+                Err((name, err)) => {
+                    eprintln!("egrep_rustico: {}: {}", name, err);
+                    error = Some(err);
+                    continue;
+                }
+            };
+            let found = GrepRustico::search_input(
+                &self.options,
+                &mut self.searcher,
+                &self.matcher,
+                &input,
+                &mut printer,
+            );
+            match found {
+                Ok(found) => matched |= found,
+                Err(err) => {
+                    eprintln!("egrep_rustico: {}: {}", input.name(), err);
+                    error = Some(err);
+                }
+            }
+            if matched && self.options.output_mode == OutputMode::Quiet {
+                return Ok(true);
+            }
+        }
 
-        Ok(cadenas)
+        match error {
+            Some(err) => Err(err),
+            None => Ok(matched),
+        }
     }
 
-    /// Lee un archivo línea por línea y lo convierte en un vector de cadenas.
+    /// Busca en varias entradas a la vez con `threads` hilos de trabajo.
     ///
-    /// # Arguments
+    /// Un hilo recorre las entradas y las reparte, cada hilo de trabajo busca con su propia
+    /// copia del `Searcher` compartiendo el `Matcher`, y el hilo actual imprime lo que encontró
+    /// cada uno en el orden del recorrido, o a medida que terminan con `--unordered`.
+    fn run_parallel<W: Write>(&self, out: &mut W, threads: usize) -> Result<bool, GrepError> {
+        let mut printer = Printer::new(out, &self.options);
+        let options = &self.options;
+        let matcher = &self.matcher;
+        let searcher = &self.searcher;
+        let inputs = self.inputs.clone();
+        let stop = &AtomicBool::new(false);
+        let (work_sender, work_receiver) = mpsc::sync_channel::<(usize, GrepInput, bool)>(threads);
+        let work_receiver = &Mutex::new(work_receiver);
+        let (result_sender, result_receiver) = mpsc::channel::<SearchOutput>();
+
+        thread::scope(|scope| {
+            let walker_sender = result_sender.clone();
+            scope.spawn(move || {
+                let mut walker = InputWalker::new(inputs, options);
+                let mut index = 0;
+                while let Some(input) = walker.next() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let with_filename = options.show_filename(walker.walked_dir());
+                    let sent = match input {
+                        Ok(input) => work_sender.send((index, input, with_filename)).is_ok(),
+                        Err(err) => walker_sender
+                            .send(SearchOutput {
+                                index,
+                                output: Vec::new(),
+                                result: Err(err),
+                            })
+                            .is_ok(),
+                    };
+                    if !sent {
+                        break;
+                    }
+                    index += 1;
+                }
+            });
+
+            for _ in 0..threads {
+                let result_sender = result_sender.clone();
+                scope.spawn(move || {
+                    let mut searcher = searcher.clone();
+                    loop {
+                        let work = match work_receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        let (index, input, with_filename) = match work {
+                            Ok(work) => work,
+                            Err(_) => break,
+                        };
+                        if stop.load(Ordering::Relaxed) {
+                            continue;
+                        }
+                        let mut printer = Printer::new(Vec::new(), options);
+                        printer.set_with_filename(with_filename);
+                        let result = GrepRustico::search_input(
+                            options,
+                            &mut searcher,
+                            matcher,
+                            &input,
+                            &mut printer,
+                        )
+                        .map_err(|err| (input.name().to_string(), err));
+                        let output = SearchOutput {
+                            index,
+                            output: printer.into_inner(),
+                            result,
+                        };
+                        let _ = result_sender.send(output);
+                    }
+                });
+            }
+            drop(result_sender);
+
+            // Se avisa que terminen antes de soltar el receptor, para que los hilos de trabajo
+            // sigan vaciando la cola y el que recorre no quede esperando para mandar.
+            let result = self.print_results(&result_receiver, &mut printer);
+            stop.store(true, Ordering::Relaxed);
+            drop(result_receiver);
+            result
+        })
+    }
+
+    /// Imprime los resultados de los hilos de trabajo a medida que llegan.
     ///
-    /// * `lector_lineas` - Un iterador sobre las líneas del archivo.
+    /// Salvo con `--unordered`, guarda los que llegan antes de tiempo hasta poder imprimirlos en
+    /// el orden del recorrido. Informa los errores en el mismo orden que la salida.
     ///
     /// # Returns
     ///
-    /// Devuelve un vector de cadenas que representan las líneas del archivo.
-    ///
-    /// Si hay un error al leer el archivo, devuelve un error de tipo `GrepError`.
-    fn read_file(lector_lineas: Lines<BufReader<&File>>) -> Result<Vec<String>, GrepError> {
-        let mut cadenas: Vec<String> = Vec::new();
+    /// Devuelve lo mismo que `run_with`.
+    fn print_results<W: Write>(
+        &self,
+        results: &Receiver<SearchOutput>,
+        printer: &mut Printer<W>,
+    ) -> Result<bool, GrepError> {
+        let separate =
+            self.searcher.separates_groups() && self.options.output_mode == OutputMode::Lines;
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let mut matched = false;
+        let mut error = None;
 
-        for linea in lector_lineas {
-            match linea {
-                Ok(linea) => cadenas.push(linea),
-                Err(_) => return Err(GrepError::ErrArchivo),
-            };
+        for search in results {
+            pending.insert(search.index, search);
+            loop {
+                let search = if self.options.unordered {
+                    pending.pop_first().map(|(_, search)| search)
+                } else {
+                    pending.remove(&next)
+                };
+                let search = match search {
+                    Some(search) => search,
+                    None => break,
+                };
+                next += 1;
+
+                printer.print_chunk(&search.output, separate)?;
+                match search.result {
+                    Ok(found) => matched |= found,
+                    Err((name, err)) => {
+                        eprintln!("egrep_rustico: {}: {}", name, err);
+                        error = Some(err);
+                    }
+                }
+                if matched && self.options.output_mode == OutputMode::Quiet {
+                    return Ok(true);
+                }
+            }
         }
 
-        Ok(cadenas)
+        match error {
+            Some(err) => Err(err),
+            None => Ok(matched),
+        }
     }
 
-    /// Filtra cada línea y ejecuta el grep para cada expresión regular.
+    /// Busca en una entrada e imprime las líneas que coinciden a medida que se encuentran,
+    /// o el resumen que corresponda al modo de salida.
+    ///
+    /// Si la entrada no está en UTF-8, según `--encoding` o su marca de orden de bytes, se
+    /// convierte a UTF-8 antes de buscar.
     ///
     /// # Arguments
     ///
-    /// * `lines` - Un vector de cadenas que representan las líneas del archivo.
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    /// * `searcher` - El `Searcher` del hilo que busca.
+    /// * `matcher` - El `Matcher`, compartido entre todos los hilos.
+    /// * `input` - La entrada en la que se va a buscar.
+    /// * `printer` - El `Printer` donde se escriben los resultados.
     ///
     /// # Returns
     ///
-    /// Devuelve un vector de cadenas que representan las líneas que coinciden con las expresiones regulares.
+    /// Devuelve `true` si alguna línea de la entrada fue seleccionada, o con `-L`,
+    /// si se listó la entrada por no tener ninguna.
     ///
-    /// Si hay un error al ejecutar el grep, devuelve un error de tipo `GrepError`.
-    fn filetr_and_run(&mut self, lines: &Vec<String>) -> Result<Vec<String>, GrepError> {
-        let mut resultado = Vec::new();
-
-        for line in lines {
-            if !line.is_ascii() {
-                return Err(GrepError::Err);
+    /// Si hay un error al leer la entrada, devuelve un error de tipo `GrepError`.
+    fn search_input<W: Write>(
+        options: &GrepOptions,
+        searcher: &mut Searcher,
+        matcher: &Matcher,
+        input: &GrepInput,
+        printer: &mut Printer<W>,
+    ) -> Result<bool, GrepError> {
+        let mut reader = input.open()?;
+        let count = match options.encoding.detect(&mut reader)? {
+            Encoding::Utf8 => {
+                let count = searcher.search(&mut reader, input.name(), matcher, printer)?;
+                GrepInput::unread(&mut reader);
+                count
             }
+            encoding => {
+                let mut decoded = BufReader::new(Transcoder::new(reader, encoding));
+                searcher.search(&mut decoded, input.name(), matcher, printer)?
+            }
+        };
 
-            for regex in &mut self.regex_vec {
-                if !resultado.contains(line) && regex.test(line)? {
-                    resultado.push(line.clone());
-                }
+        match options.output_mode {
+            OutputMode::Count => printer.print_count(input.name(), count)?,
+            OutputMode::FilesWithMatches if count > 0 => printer.print_filename(input.name())?,
+            OutputMode::FilesWithoutMatch if count == 0 => {
+                printer.print_filename(input.name())?;
+                return Ok(true);
             }
+            OutputMode::FilesWithoutMatch => return Ok(false),
+            _ => {}
+        }
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test_files::write_temp;
+
+    fn grep(values: &[&str]) -> Result<GrepRustico, GrepError> {
+        let mut args = vec!["egrep_rustico".to_string()];
+        args.extend(values.iter().map(|value| value.to_string()));
+        GrepRustico::read_commands(args)
+    }
+
+    fn output(grep: &mut GrepRustico) -> (Result<bool, GrepError>, String) {
+        let mut out = Vec::new();
+        let result = grep.run_with(&mut out);
+        (result, String::from_utf8(out).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_ignore_case() -> Result<(), GrepError> {
+        let mut grep = grep(&["-i", "^BAN|^MELON$", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "banana\nmelon\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_strings() -> Result<(), GrepError> {
+        let mut fixed = grep(&["-F", "-e", "abc?def", "-e", "123*456", "src/frutas.txt"])?;
+        let (result, out) = output(&mut fixed);
+        assert!(result?);
+        assert_eq!(out, "abc?def\n123*456\n");
+
+        let mut fixed = grep(&["-Fiwo", "-e", "MELON", "-e", "END", "src/frutas.txt"])?;
+        let (_, out) = output(&mut fixed);
+        assert_eq!(out, "melon\nend\nend\nend\nmelon\n");
+
+        let mut fixed = grep(&["-Fx", "abcd", "src/frutas.txt"])?;
+        let (_, out) = output(&mut fixed);
+        assert_eq!(out, "abcd\nabcd\n");
+
+        let mut fixed = grep(&["-F", "[[:digit:]]|x", "src/frutas.txt"])?;
+        let (result, _) = output(&mut fixed);
+        assert!(!result?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_newline_separates_patterns() -> Result<(), GrepError> {
+        let mut regex = grep(&["^ban\nmelon$", "src/frutas.txt"])?;
+        let (result, out) = output(&mut regex);
+        assert!(result?);
+        assert_eq!(out, "banana\nmelon\nwatermelon\nsoy melon\n");
+
+        let mut fixed = grep(&["-F", "-e", "8\nabc?", "src/frutas.txt"])?;
+        let (result, out) = output(&mut fixed);
+        assert!(result?);
+        assert_eq!(out, "8\nabc?def\n789+10\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_basic_regexp() -> Result<(), GrepError> {
+        let mut basic = grep(&["-G", "^ban\\|[[:digit:]]+1", "src/frutas.txt"])?;
+        let (result, out) = output(&mut basic);
+        assert!(result?);
+        assert_eq!(out, "banana\n789+10\n");
+
+        let mut basic = grep(&["-G", "-o", "b\\+c?d", "src/frutas.txt"])?;
+        let (_, out) = output(&mut basic);
+        assert_eq!(out, "bc?d\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_file_without_prefix() -> Result<(), GrepError> {
+        let mut grep = grep(&["^ban", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "banana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_files_with_prefix() -> Result<(), GrepError> {
+        let mut grep = grep(&["^ban", "src/frutas.txt", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "src/frutas.txt:banana\nsrc/frutas.txt:banana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_filename() -> Result<(), GrepError> {
+        let mut grep = grep(&["-h", "^ban", "src/frutas.txt", "src/frutas.txt"])?;
+        let (_, out) = output(&mut grep);
+        assert_eq!(out, "banana\nbanana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiple_patterns_word_regexp() -> Result<(), GrepError> {
+        let mut grep = grep(&["-w", "-e", "melon", "-e", "^abc", "src/frutas.txt"])?;
+        let (_, out) = output(&mut grep);
+        assert_eq!(out, "melon\nabc?def\nsoy melon\nabc\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_count() -> Result<(), GrepError> {
+        let mut grep = grep(&["-c", "melon", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "4\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_files_with_and_without_matches() -> Result<(), GrepError> {
+        let mut with = grep(&["-l", "melon", "src/frutas.txt", "Cargo.toml"])?;
+        let (result, out) = output(&mut with);
+        assert!(result?);
+        assert_eq!(out, "src/frutas.txt\n");
+
+        let mut without = grep(&["-L", "melon", "src/frutas.txt", "Cargo.toml"])?;
+        let (result, out) = output(&mut without);
+        assert!(result?);
+        assert_eq!(out, "Cargo.toml\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_quiet_ignores_later_errors() -> Result<(), GrepError> {
+        let mut grep = grep(&["-q", "melon", "src/frutas.txt", "no_existe.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_count_per_file() -> Result<(), GrepError> {
+        let mut grep = grep(&["-m", "1", "melon", "src/frutas.txt", "src/frutas.txt"])?;
+        let (_, out) = output(&mut grep);
+        assert_eq!(out, "src/frutas.txt:melon\nsrc/frutas.txt:melon\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_matching() -> Result<(), GrepError> {
+        let mut grep = grep(&["-on", "-e", "melon", "-e", "^ban", "src/frutas.txt"])?;
+        let (_, out) = output(&mut grep);
+        assert_eq!(out, "1:ban\n5:melon\n6:melon\n21:melon\n22:melon\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive() -> Result<(), GrepError> {
+        let mut grep = grep(&["-r", "--include=*.txt", "--sort=path", "^ban", "src"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "src/frutas.txt:banana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive_filename_prefix() -> Result<(), GrepError> {
+        for threads in ["-j1", "-j2"] {
+            let mut file = grep(&[threads, "-r", "^ban", "src/frutas.txt"])?;
+            let (result, out) = output(&mut file);
+            assert!(result?);
+            assert_eq!(out, "banana\n");
+
+            let args = [threads, "-r", "--include=frutas.txt", "^ban", "src"];
+            let mut dir = grep(&args)?;
+            let (result, out) = output(&mut dir);
+            assert!(result?);
+            assert_eq!(out, "src/frutas.txt:banana\n");
         }
-        Ok(resultado)
+        Ok(())
+    }
+
+    #[test]
+    fn test_directory_without_recursion() -> Result<(), GrepError> {
+        let mut grep = grep(&["^ban", "src", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result.is_err());
+        assert_eq!(out, "src/frutas.txt:banana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_keeps_order() -> Result<(), GrepError> {
+        let files = [
+            "src/frutas.txt",
+            "Cargo.toml",
+            "no_existe.txt",
+            "src/frutas.txt",
+        ];
+        let mut args = vec!["-n", "-A1", "melon$"];
+        args.extend(files);
+
+        let mut sequential = grep(&[&["-j1"], args.as_slice()].concat())?;
+        let (sequential_result, expected) = output(&mut sequential);
+        let mut parallel = grep(&[&["-j4"], args.as_slice()].concat())?;
+        let (result, out) = output(&mut parallel);
+        assert!(sequential_result.is_err());
+        assert!(result.is_err());
+        assert_eq!(out, expected);
+        assert!(out.contains("va\n--\nsrc/frutas.txt:5:melon\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_unordered() -> Result<(), GrepError> {
+        let mut grep = grep(&[
+            "--unordered",
+            "-j3",
+            "-c",
+            "melon",
+            "src/frutas.txt",
+            "src/frutas.txt",
+            "Cargo.toml",
+        ])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        let mut lines: Vec<&str> = out.lines().collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec!["Cargo.toml:0", "src/frutas.txt:4", "src/frutas.txt:4"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_quiet() -> Result<(), GrepError> {
+        let mut grep = grep(&[
+            "-q",
+            "-j2",
+            "melon",
+            "no_existe.txt",
+            "src/frutas.txt",
+            "Cargo.toml",
+        ])?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_quiet_many_inputs() -> Result<(), GrepError> {
+        let content = format!("{}melon\n", "kiwi\n".repeat(50_000));
+        let large = write_temp("quiet_large.txt", content.as_bytes())?;
+        let mut args = vec!["-q", "-j2", "melon"];
+        args.extend([large.as_str(); 10]);
+        let mut grep = grep(&args)?;
+        let (result, out) = output(&mut grep);
+        assert!(result?);
+        assert_eq!(out, "");
+
+        let _ = fs::remove_file(&large);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encodings() -> Result<(), GrepError> {
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(
+            "id,año\r\n1,niño\r\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        let utf16 = write_temp("encoding_utf16.csv", &bytes)?;
+        let latin1 = write_temp("encoding_latin1.txt", b"caf\xe9\nt\xe9\n")?;
+
+        let mut from_utf16 = grep(&["-h", "ni.o", &utf16])?;
+        let (result, out) = output(&mut from_utf16);
+        assert!(result?);
+        assert_eq!(out, "1,niño\r\n");
+
+        let mut from_latin1 = grep(&["-h", "--encoding=latin1", "^t.$", &latin1])?;
+        let (result, out) = output(&mut from_latin1);
+        assert!(result?);
+        assert_eq!(out, "té\n");
+
+        let _ = fs::remove_file(&utf16);
+        let _ = fs::remove_file(&latin1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_files() -> Result<(), GrepError> {
+        let list = write_temp("lista.txt", b"^ban\nmel.n$\n")?;
+
+        let mut from_file = grep(&["-f", &list, "-e", "^abc$", "src/frutas.txt"])?;
+        let (result, out) = output(&mut from_file);
+        assert!(result?);
+        assert_eq!(out, "banana\nmelon\nwatermelon\nsoy melon\nabc\n");
+
+        let _ = fs::remove_file(&list);
+        Ok(())
+    }
+
+    #[test]
+    fn test_continues_past_missing_file() -> Result<(), GrepError> {
+        let mut grep = grep(&["^ban", "no_existe.txt", "src/frutas.txt"])?;
+        let (result, out) = output(&mut grep);
+        assert!(result.is_err());
+        assert_eq!(out, "src/frutas.txt:banana\n");
+        Ok(())
     }
 }
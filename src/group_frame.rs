@@ -0,0 +1,45 @@
+use std::mem;
+
+use crate::{regex_flags::RegexFlags, regex_step::RegexStep};
+
+/// Representa un grupo que el parser de expresiones regulares está leyendo, o el patrón
+/// entero si es el primero de la pila.
+#[derive(Debug)]
+pub struct GroupFrame {
+    /// Las alternativas del grupo que ya terminaron, separadas por `|`.
+    pub alternatives: Vec<Vec<RegexStep>>,
+    /// Los pasos de la alternativa que se está leyendo.
+    pub steps: Vec<RegexStep>,
+    /// Los modificadores con los que empieza cada alternativa del grupo.
+    pub flags: RegexFlags,
+    /// Los modificadores que vuelven a valer después del `)`.
+    pub outer_flags: RegexFlags,
+}
+
+impl GroupFrame {
+    /// Crea un `GroupFrame` vacío.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - Los modificadores con los que empieza cada alternativa del grupo.
+    /// * `outer_flags` - Los modificadores que vuelven a valer después del grupo.
+    pub fn new(flags: RegexFlags, outer_flags: RegexFlags) -> GroupFrame {
+        GroupFrame {
+            alternatives: Vec::new(),
+            steps: Vec::new(),
+            flags,
+            outer_flags,
+        }
+    }
+
+    /// Termina la alternativa que se está leyendo y empieza una nueva.
+    pub fn next_alternative(&mut self) {
+        self.alternatives.push(mem::take(&mut self.steps));
+    }
+
+    /// Termina el grupo y devuelve los pasos de cada una de sus alternativas.
+    pub fn finish(mut self) -> Vec<Vec<RegexStep>> {
+        self.next_alternative();
+        self.alternatives
+    }
+}
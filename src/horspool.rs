@@ -0,0 +1,113 @@
+use crate::utf8;
+
+/// Busca un texto fijo con el algoritmo de Boyer-Moore-Horspool.
+///
+/// Compara la ventana contra el texto buscado y, si no coincide, la corre según el último byte
+/// de la ventana, salteando las posiciones en las que el texto no puede empezar.
+#[derive(Debug, Clone)]
+pub struct Horspool {
+    needle: Vec<u8>,
+    shift: Vec<usize>,
+}
+
+impl Horspool {
+    /// Prepara la búsqueda de `needle`.
+    ///
+    /// # Arguments
+    ///
+    /// * `needle` - El texto a buscar, en bytes.
+    pub fn new(needle: &[u8]) -> Horspool {
+        let mut shift = vec![needle.len().max(1); 256];
+        for (i, &byte) in needle
+            .iter()
+            .enumerate()
+            .take(needle.len().saturating_sub(1))
+        {
+            shift[usize::from(byte)] = needle.len() - 1 - i;
+        }
+        Horspool {
+            needle: needle.to_vec(),
+            shift,
+        }
+    }
+
+    /// Devuelve el largo, en bytes, del texto buscado.
+    pub fn max_len(&self) -> usize {
+        self.needle.len()
+    }
+
+    /// Recorre de izquierda a derecha las apariciones del texto que empiezan en `start` o
+    /// después, incluidas las que se superponen.
+    ///
+    /// # Arguments
+    ///
+    /// * `haystack` - Los bytes en los que se busca.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    /// * `found` - Recibe el inicio y el fin de cada aparición; si devuelve `false` se deja de
+    ///   buscar.
+    pub fn each_match(
+        &self,
+        haystack: &[u8],
+        start: usize,
+        found: &mut dyn FnMut(usize, usize) -> bool,
+    ) {
+        let len = self.needle.len();
+        if len == 0 {
+            let mut pos = start;
+            while pos <= haystack.len() && found(pos, pos) {
+                pos += utf8::decode(&haystack[pos..]).map_or(1, |(_, size)| size);
+            }
+            return;
+        }
+
+        let mut pos = start;
+        while pos + len <= haystack.len() {
+            let window = &haystack[pos..pos + len];
+            if window == self.needle.as_slice() && !found(pos, pos + len) {
+                return;
+            }
+            pos += self.shift[usize::from(window[len - 1])];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(needle: &str, haystack: &str, start: usize) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        Horspool::new(needle.as_bytes()).each_match(haystack.as_bytes(), start, &mut |s, e| {
+            found.push((s, e));
+            true
+        });
+        found
+    }
+
+    #[test]
+    fn test_each_match() {
+        assert_eq!(
+            matches("a.b", "xa.b a.ba.b", 0),
+            vec![(1, 4), (5, 8), (8, 11)]
+        );
+        assert_eq!(matches("a.b", "xa.b a.ba.b", 2), vec![(5, 8), (8, 11)]);
+        assert_eq!(matches("aa", "aaa", 0), vec![(0, 2), (1, 3)]);
+        assert_eq!(matches("[0]", "a.b[1]", 0), vec![]);
+        assert_eq!(matches("ñu", "el ñu", 0), vec![(3, 6)]);
+    }
+
+    #[test]
+    fn test_empty_needle() {
+        assert_eq!(matches("", "añ", 0), vec![(0, 0), (1, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn test_stops() {
+        let mut count = 0;
+        Horspool::new(b"a").each_match(b"aaaa", 0, &mut |_, _| {
+            count += 1;
+            count < 2
+        });
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::glob::Glob;
+
+/// Representa una línea de un archivo `.gitignore`, `.ignore` o `.git/info/exclude`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreRule {
+    glob: Glob,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    /// Interpreta una línea con la sintaxis de `.gitignore`.
+    ///
+    /// Un `!` inicial niega la regla, una `/` final la restringe a directorios y una `/` al
+    /// principio o en el medio la ancla al directorio del archivo; si no, se compara con el
+    /// nombre en cualquier nivel. `\#` y `\!` permiten empezar con esos caracteres.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La línea del archivo, sin el terminador.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `None` si la línea está vacía o es un comentario.
+    pub fn parse(line: &str) -> Option<IgnoreRule> {
+        let mut pattern = IgnoreRule::trim_trailing_spaces(line);
+        if pattern.is_empty() || pattern.starts_with('#') {
+            return None;
+        }
+
+        let negated = pattern.starts_with('!');
+        if negated || pattern.starts_with("\\!") || pattern.starts_with("\\#") {
+            pattern = &pattern[1..];
+        }
+
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        if pattern.is_empty() {
+            return None;
+        }
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        Some(IgnoreRule {
+            glob: Glob::new(pattern),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// Indica si la regla se aplica a la ruta.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - La ruta, separada por `/`, relativa al directorio del archivo de la regla.
+    /// * `is_dir` - Si la ruta es un directorio.
+    pub fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.anchored {
+            self.glob.matches(path)
+        } else {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            self.glob.matches(name)
+        }
+    }
+
+    /// Indica si la regla es una negación, es decir, si vuelve a incluir lo que coincide.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Saca los espacios finales que no estén escapados con `\`.
+    fn trim_trailing_spaces(line: &str) -> &str {
+        let trimmed = line.trim_end_matches(' ');
+        if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+            &line[..trimmed.len() + 1]
+        } else {
+            trimmed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grep_error::GrepError;
+
+    fn parse(line: &str) -> Result<IgnoreRule, GrepError> {
+        IgnoreRule::parse(line).ok_or(GrepError::Err)
+    }
+
+    #[test]
+    fn test_parse_comments_and_blank() {
+        assert_eq!(IgnoreRule::parse(""), None);
+        assert_eq!(IgnoreRule::parse("# comentario"), None);
+        assert_eq!(IgnoreRule::parse("   "), None);
+        assert!(IgnoreRule::parse("\\#archivo").is_some_and(|rule| rule.matches("#archivo", false)));
+    }
+
+    #[test]
+    fn test_unanchored() -> Result<(), GrepError> {
+        let rule = parse("*.log")?;
+        assert!(rule.matches("a.log", false));
+        assert!(rule.matches("sub/dir/a.log", false));
+        assert!(!rule.is_negated());
+        Ok(())
+    }
+
+    #[test]
+    fn test_anchored_and_dir_only() -> Result<(), GrepError> {
+        let rule = parse("/target/")?;
+        assert!(rule.matches("target", true));
+        assert!(!rule.matches("target", false));
+        assert!(!rule.matches("sub/target", true));
+
+        let rule = parse("doc/*.txt")?;
+        assert!(rule.matches("doc/a.txt", false));
+        assert!(!rule.matches("sub/doc/a.txt", false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_negated_and_escaped() -> Result<(), GrepError> {
+        let rule = parse("!keep.log")?;
+        assert!(rule.is_negated());
+        assert!(rule.matches("keep.log", false));
+
+        let rule = parse("\\!raro  ")?;
+        assert!(!rule.is_negated());
+        assert!(rule.matches("!raro", false));
+
+        let rule = parse("espacio\\ ")?;
+        assert!(rule.matches("espacio ", false));
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fs, path::Path};
+
+use crate::ignore_rule::IgnoreRule;
+
+/// Representa las reglas de un archivo `.gitignore`, `.ignore` o `.git/info/exclude`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreRules {
+    prefix: String,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Interpreta el contenido de un archivo de reglas.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - El contenido del archivo.
+    /// * `prefix` - La ruta, terminada en `/`, desde el directorio del archivo hasta el directorio
+    ///   con el que se van a comparar las rutas; vacía si es el mismo.
+    pub fn parse(content: &str, prefix: &str) -> IgnoreRules {
+        IgnoreRules {
+            prefix: prefix.to_string(),
+            rules: content
+                .lines()
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .filter_map(IgnoreRule::parse)
+                .collect(),
+        }
+    }
+
+    /// Lee un archivo de reglas.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - La ruta del archivo.
+    /// * `prefix` - Igual que en `parse`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `None` si el archivo no existe, no se puede leer o no tiene reglas.
+    pub fn from_file(path: &Path, prefix: &str) -> Option<IgnoreRules> {
+        let content = fs::read_to_string(path).ok()?;
+        let rules = IgnoreRules::parse(&content, prefix);
+        (!rules.rules.is_empty()).then_some(rules)
+    }
+
+    /// Decide si la ruta se ignora según la última regla que coincide con ella.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - La ruta, separada por `/`, relativa al directorio con el que se comparan.
+    /// * `is_dir` - Si la ruta es un directorio.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `Some(true)` si se ignora, `Some(false)` si una negación la vuelve a incluir,
+    /// o `None` si ninguna regla coincide.
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> Option<bool> {
+        let path = format!("{}{}", self.prefix, path);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&path, is_dir))
+            .map(|rule| !rule.is_negated())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_rule_wins() {
+        let rules = IgnoreRules::parse("*.log\n!keep.log\n# comentario\n\nbuild/\n", "");
+        assert_eq!(rules.is_ignored("a.log", false), Some(true));
+        assert_eq!(rules.is_ignored("sub/keep.log", false), Some(false));
+        assert_eq!(rules.is_ignored("build", true), Some(true));
+        assert_eq!(rules.is_ignored("build", false), None);
+        assert_eq!(rules.is_ignored("main.rs", false), None);
+    }
+
+    #[test]
+    fn test_prefix() {
+        let rules = IgnoreRules::parse("/sub/gen/\n**/tmp/*.txt\n", "sub/");
+        assert_eq!(rules.is_ignored("gen", true), Some(true));
+        assert_eq!(rules.is_ignored("x/gen", true), None);
+        assert_eq!(rules.is_ignored("x/tmp/a.txt", false), Some(true));
+    }
+
+    #[test]
+    fn test_from_missing_file() {
+        assert_eq!(
+            IgnoreRules::from_file(Path::new("no_existe/.gitignore"), ""),
+            None
+        );
+    }
+}
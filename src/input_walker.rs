@@ -0,0 +1,438 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    vec,
+};
+
+use crate::{
+    glob::Glob, grep_error::GrepError, grep_input::GrepInput, grep_options::GrepOptions,
+    ignore_rules::IgnoreRules, recursion::Recursion, walk_frame::WalkFrame,
+};
+
+/// Recorre las entradas pasadas por línea de comandos y, con `-r` o `-R`, los directorios,
+/// devolviendo los archivos en los que hay que buscar.
+///
+/// Salvo que se use `--no-ignore`, al recorrer directorios saltea lo que indican los archivos
+/// `.gitignore`, `.ignore` y `.git/info/exclude`, con la misma prioridad que les da git.
+///
+/// Cada elemento es la entrada a buscar, o el nombre de lo que no se pudo recorrer junto con el
+/// error, para informarlo y seguir con lo demás.
+#[derive(Debug)]
+pub struct InputWalker {
+    roots: vec::IntoIter<GrepInput>,
+    stack: Vec<WalkFrame>,
+    recursion: Recursion,
+    max_depth: Option<usize>,
+    sort_paths: bool,
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+    exclude_dir: Vec<Glob>,
+    omit_dot_slash: bool,
+    ignore: bool,
+    walked_dir: bool,
+}
+
+impl InputWalker {
+    /// Crea un `InputWalker` que recorre las entradas según las opciones.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Las entradas pasadas por línea de comandos.
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    pub fn new(inputs: Vec<GrepInput>, options: &GrepOptions) -> InputWalker {
+        InputWalker {
+            roots: inputs.into_iter(),
+            stack: Vec::new(),
+            recursion: options.recursion,
+            max_depth: options.max_depth,
+            sort_paths: options.sort_paths,
+            include: options.include.clone(),
+            exclude: options.exclude.clone(),
+            exclude_dir: options.exclude_dir.clone(),
+            omit_dot_slash: options.files.is_empty(),
+            ignore: !options.no_ignore,
+            walked_dir: false,
+        }
+    }
+
+    /// Indica si el recorrido ya entró en algún directorio, para saber si hay que mostrar el
+    /// nombre de los archivos encontrados.
+    pub fn walked_dir(&self) -> bool {
+        self.walked_dir
+    }
+
+    /// Visita una ruta, ya sea un operando o algo encontrado dentro de un directorio.
+    ///
+    /// Si es un directorio en el que hay que entrar, agrega su contenido a la pila. Con `-R`, no
+    /// entra en un directorio que ya se está recorriendo, para no quedar en un ciclo de enlaces.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - La ruta a visitar.
+    /// * `depth` - La profundidad de la ruta; los operandos tienen profundidad 0.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la entrada a buscar o el error a informar, o `None` si no hay nada que devolver.
+    fn visit(
+        &mut self,
+        path: PathBuf,
+        depth: usize,
+    ) -> Option<Result<GrepInput, (String, GrepError)>> {
+        let operand = depth == 0;
+        let name = self.name_of(&path);
+        let metadata = if operand || self.recursion == Recursion::FollowLinks {
+            fs::metadata(&path)
+        } else {
+            fs::symlink_metadata(&path)
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(_) if operand => return Some(Ok(GrepInput::Archivo(name))),
+            Err(_) => return Some(Err((name, GrepError::ErrArchivo))),
+        };
+
+        if !operand && self.is_ignored(&path, metadata.is_dir()) {
+            return None;
+        }
+
+        if metadata.is_dir() {
+            if self.recursion == Recursion::Off {
+                return Some(Err((name, GrepError::ErrDirectorio)));
+            }
+            if !operand && self.matches_any(&self.exclude_dir, &path) {
+                return None;
+            }
+            if self.max_depth.is_some_and(|max| depth >= max) {
+                return None;
+            }
+            let dir = match self.recursion {
+                Recursion::FollowLinks => fs::canonicalize(&path).unwrap_or_else(|_| path.clone()),
+                _ => path.clone(),
+            };
+            if self.stack.iter().any(|frame| frame.dir == dir) {
+                return None;
+            }
+            return match self.read_dir(&path) {
+                Ok(entries) => {
+                    let ignore = self.read_ignore(&path, operand);
+                    self.walked_dir = true;
+                    self.stack.push(WalkFrame {
+                        entries: entries.into_iter(),
+                        depth: depth + 1,
+                        path,
+                        dir,
+                        ignore,
+                    });
+                    None
+                }
+                Err(err) => Some(Err((name, err))),
+            };
+        }
+
+        if !operand && !metadata.is_file() {
+            return None;
+        }
+        if self.matches_any(&self.exclude, &path)
+            || (!self.include.is_empty() && !self.matches_any(&self.include, &path))
+        {
+            return None;
+        }
+        Some(Ok(GrepInput::Archivo(name)))
+    }
+
+    /// Lee el contenido de un directorio, ordenado por nombre si se pidió `--sort=path`.
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>, GrepError> {
+        let entries = fs::read_dir(path).map_err(|_| GrepError::ErrArchivo)?;
+        let mut paths = Vec::new();
+        for entry in entries {
+            paths.push(entry.map_err(|_| GrepError::ErrArchivo)?.path());
+        }
+        if self.sort_paths {
+            paths.sort();
+        }
+        Ok(paths)
+    }
+
+    /// Indica si alguna regla de los directorios que se están recorriendo ignora la ruta.
+    ///
+    /// Las reglas del directorio más cercano tienen prioridad, y dentro de un mismo directorio,
+    /// las de `.ignore` sobre las de `.gitignore`, y éstas sobre las de `.git/info/exclude`.
+    /// El directorio `.git` siempre se ignora.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if !self.ignore {
+            return false;
+        }
+        if is_dir && path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+        for frame in self.stack.iter().rev() {
+            let relative = match path.strip_prefix(&frame.path) {
+                Ok(relative) => InputWalker::slash_path(relative),
+                Err(_) => continue,
+            };
+            for rules in frame.ignore.iter().rev() {
+                if let Some(ignored) = rules.is_ignored(&relative, is_dir) {
+                    return ignored;
+                }
+            }
+        }
+        false
+    }
+
+    /// Lee las reglas para ignorar de un directorio en el que se va a entrar.
+    ///
+    /// Si es un operando, también lee las de los directorios que lo contienen, hasta la raíz del
+    /// repositorio; si no está dentro de un repositorio, sólo las del propio directorio.
+    fn read_ignore(&self, path: &Path, operand: bool) -> Vec<IgnoreRules> {
+        let mut ignore = Vec::new();
+        if !self.ignore {
+            return ignore;
+        }
+
+        if operand {
+            if let Ok(canonical) = fs::canonicalize(path) {
+                let mut ancestors = Vec::new();
+                for ancestor in canonical.ancestors().skip(1) {
+                    ancestors.push(ancestor);
+                    if ancestor.join(".git").exists() {
+                        for ancestor in ancestors.iter().rev() {
+                            let prefix = match canonical.strip_prefix(ancestor) {
+                                Ok(relative) => format!("{}/", InputWalker::slash_path(relative)),
+                                Err(_) => continue,
+                            };
+                            InputWalker::read_ignore_files(ancestor, &prefix, &mut ignore);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        InputWalker::read_ignore_files(path, "", &mut ignore);
+        ignore
+    }
+
+    /// Agrega las reglas de `.git/info/exclude`, `.gitignore` e `.ignore` de un directorio,
+    /// en ese orden de prioridad.
+    fn read_ignore_files(dir: &Path, prefix: &str, ignore: &mut Vec<IgnoreRules>) {
+        let files = [
+            dir.join(".git").join("info").join("exclude"),
+            dir.join(".gitignore"),
+            dir.join(".ignore"),
+        ];
+        ignore.extend(
+            files
+                .iter()
+                .filter_map(|file| IgnoreRules::from_file(file, prefix)),
+        );
+    }
+
+    /// Arma una ruta relativa separada por `/`, como la esperan las reglas para ignorar.
+    fn slash_path(path: &Path) -> String {
+        path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Indica si el nombre del último componente de la ruta coincide con alguno de los globs.
+    fn matches_any(&self, globs: &[Glob], path: &Path) -> bool {
+        let file_name = match path.file_name() {
+            Some(file_name) => file_name.to_string_lossy(),
+            None => path.to_string_lossy(),
+        };
+        globs.iter().any(|glob| glob.matches(&file_name))
+    }
+
+    /// Devuelve el nombre con el que se muestra la ruta, sin el `./` inicial si se está
+    /// recorriendo el directorio actual porque no se pasó ningún archivo.
+    fn name_of(&self, path: &Path) -> String {
+        let path = match path.strip_prefix(".") {
+            Ok(stripped) if self.omit_dot_slash && !stripped.as_os_str().is_empty() => stripped,
+            _ => path,
+        };
+        path.to_string_lossy().into_owned()
+    }
+}
+
+impl Iterator for InputWalker {
+    type Item = Result<GrepInput, (String, GrepError)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(frame) = self.stack.last_mut() {
+                let depth = frame.depth;
+                match frame.entries.next() {
+                    Some(path) => {
+                        if let Some(item) = self.visit(path, depth) {
+                            return Some(item);
+                        }
+                    }
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+                continue;
+            }
+
+            match self.roots.next()? {
+                GrepInput::Stdin => return Some(Ok(GrepInput::Stdin)),
+                GrepInput::Archivo(name) => {
+                    if let Some(item) = self.visit(PathBuf::from(name), 0) {
+                        return Some(item);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{glob::Glob, test_files};
+
+    fn tree(name: &str, files: &[&str]) -> Result<PathBuf, GrepError> {
+        let root = test_files::temp_path(name);
+        let _ = fs::remove_dir_all(&root);
+        for file in files {
+            write(&root, file, "hola\n")?;
+        }
+        Ok(root)
+    }
+
+    fn write(root: &Path, file: &str, content: &str) -> Result<(), GrepError> {
+        test_files::write(&root.join(file), content.as_bytes())
+    }
+
+    fn walk(root: &Path, options: GrepOptions) -> Vec<String> {
+        let root = root.to_string_lossy().into_owned();
+        let options = GrepOptions {
+            files: vec![root.clone()],
+            sort_paths: true,
+            ..options
+        };
+        InputWalker::new(vec![GrepInput::Archivo(root.clone())], &options)
+            .map(|item| match item {
+                Ok(input) => input.name().replacen(&root, "", 1),
+                Err((name, _)) => format!("error {}", name.replacen(&root, "", 1)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_directory_without_recursion() -> Result<(), GrepError> {
+        let root = tree("sin_r", &["a.txt"])?;
+        assert_eq!(walk(&root, GrepOptions::default()), vec!["error "]);
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+
+    #[test]
+    fn test_recursive_sorted() -> Result<(), GrepError> {
+        let root = tree("r", &["b.txt", "a/z.rs", "a/c/d.txt", "c.rs"])?;
+        let options = GrepOptions {
+            recursion: Recursion::Recursive,
+            ..GrepOptions::default()
+        };
+        assert_eq!(
+            walk(&root, options),
+            vec!["/a/c/d.txt", "/a/z.rs", "/b.txt", "/c.rs"]
+        );
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+
+    #[test]
+    fn test_include_exclude() -> Result<(), GrepError> {
+        let root = tree("globs", &["b.txt", "a/z.rs", "target/x.rs", "c.rs"])?;
+        let options = GrepOptions {
+            recursion: Recursion::Recursive,
+            include: vec![Glob::new("*.rs")],
+            exclude: vec![Glob::new("c.*")],
+            exclude_dir: vec![Glob::new("tar*")],
+            ..GrepOptions::default()
+        };
+        assert_eq!(walk(&root, options), vec!["/a/z.rs"]);
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+
+    #[test]
+    fn test_max_depth() -> Result<(), GrepError> {
+        let root = tree("depth", &["a.txt", "b/c.txt", "b/d/e.txt"])?;
+        let options = GrepOptions {
+            recursion: Recursion::Recursive,
+            max_depth: Some(2),
+            ..GrepOptions::default()
+        };
+        assert_eq!(walk(&root, options), vec!["/a.txt", "/b/c.txt"]);
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks() -> Result<(), GrepError> {
+        let root = tree("links", &["a/b.txt"])?;
+        std::os::unix::fs::symlink(root.join("a"), root.join("enlace"))
+            .map_err(|_| GrepError::ErrArchivo)?;
+        std::os::unix::fs::symlink(&root, root.join("a/ciclo"))
+            .map_err(|_| GrepError::ErrArchivo)?;
+        let options = GrepOptions {
+            recursion: Recursion::Recursive,
+            ..GrepOptions::default()
+        };
+        assert_eq!(walk(&root, options), vec!["/a/b.txt"]);
+
+        let options = GrepOptions {
+            recursion: Recursion::FollowLinks,
+            ..GrepOptions::default()
+        };
+        assert_eq!(walk(&root, options), vec!["/a/b.txt", "/enlace/b.txt"]);
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+
+    #[test]
+    fn test_ignore_files() -> Result<(), GrepError> {
+        let root = tree(
+            "ignore",
+            &[
+                "a.log",
+                "keep.log",
+                "main.rs",
+                "target/x.rs",
+                "sub/gen/y.rs",
+                "sub/z.rs",
+                "sub/w.tmp",
+            ],
+        )?;
+        write(&root, ".git/info/exclude", "*.tmp\n")?;
+        write(&root, ".git/HEAD", "ref\n")?;
+        write(&root, ".gitignore", "target/\n*.log\n!keep.log\n")?;
+        write(&root, "sub/.gitignore", "/gen/\n!*.tmp\n")?;
+        write(&root, "sub/.ignore", "z.rs\n")?;
+        let options = GrepOptions {
+            recursion: Recursion::Recursive,
+            ..GrepOptions::default()
+        };
+        assert_eq!(
+            walk(&root, options.clone()),
+            vec![
+                "/.gitignore",
+                "/keep.log",
+                "/main.rs",
+                "/sub/.gitignore",
+                "/sub/.ignore",
+                "/sub/w.tmp"
+            ]
+        );
+
+        let sub = walk(&root.join("sub"), options.clone());
+        assert_eq!(sub, vec!["/.gitignore", "/.ignore", "/w.tmp"]);
+
+        let options = GrepOptions {
+            no_ignore: true,
+            ..options
+        };
+        assert_eq!(walk(&root, options).len(), 12);
+        fs::remove_dir_all(&root).map_err(|_| GrepError::ErrArchivo)
+    }
+}
@@ -1,19 +1,87 @@
+// Los tests originales comparan con `assert_eq!(..., true)` y se dejan como están.
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+pub mod aho_corasick;
+
 pub mod anchoring;
 
-pub mod evaluated_step;
+pub mod binary_files;
+
+pub mod color_choice;
+
+pub mod colors;
+
+pub mod dialect;
+
+pub mod encoding;
+
+pub mod escape;
+
+pub mod fixed_strings;
+
+pub mod glob;
+
+pub mod glob_token;
 
 pub mod grep_error;
 
+pub mod group_frame;
+
+pub mod grep_input;
+
+pub mod grep_options;
+
 pub mod grep_rustico;
 
+pub mod horspool;
+
+pub mod ignore_rule;
+
+pub mod ignore_rules;
+
+pub mod input_walker;
+
 pub mod regex;
 
 pub mod character_class;
 
+pub mod regex_flags;
+
 pub mod regex_rep;
 
+pub mod regex_set;
+
 pub mod regex_step;
 
 pub mod regex_value;
 
+pub mod match_iter;
+
+pub mod matcher;
+
+pub mod output_mode;
+
+pub mod pattern;
+
+pub mod printer;
+
+pub mod record_split;
+
+pub mod recursion;
+
+pub mod search_line;
+
+pub mod search_output;
+
+pub mod searcher;
+
+#[cfg(test)]
+mod test_files;
+
+pub mod transcoder;
+
+pub mod utf8;
+
+pub mod walk_frame;
+
 pub mod bracket_expression;
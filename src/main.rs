@@ -1,5 +1,5 @@
-use std::env;
-use trabajo_practico::{grep_error::GrepError, grep_rustico::GrepRustico};
+use std::{env, process};
+use trabajo_practico::grep_rustico::GrepRustico;
 
 /// Ejecuta el código para leer la línea de comandos de la terminal y, si no hay errores, corre el programa.
 ///
@@ -9,21 +9,27 @@ use trabajo_practico::{grep_error::GrepError, grep_rustico::GrepRustico};
 ///
 /// # Returns
 ///
-/// Devuelve `Ok(())` si el programa se ejecuta correctamente, de lo contrario devuelve un error de tipo `GrepError`.
-fn egrep(args: Vec<String>) -> Result<(), GrepError> {
+/// Devuelve el código de salida: `0` si alguna línea coincidió, `1` si ninguna coincidió
+/// y `2` si hubo algún error.
+fn egrep(args: Vec<String>) -> i32 {
     let mut grep = match GrepRustico::read_commands(args) {
         Ok(grep) => grep,
-        Err(e) => return Err(e),
+        Err(e) => {
+            eprintln!("egrep_rustico: {}", e);
+            return 2;
+        }
     };
 
-    let _ = grep.run();
-
-    Ok(())
+    match grep.run() {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(_) => 2,
+    }
 }
 
 /// Toma los valores de la línea de comandos y ejecuta el programa.
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let _ = egrep(args);
+    process::exit(egrep(args));
 }
@@ -0,0 +1,73 @@
+use crate::{matcher::Matcher, utf8};
+
+/// Recorre las coincidencias de una línea de izquierda a derecha, sin superponerse y
+/// salteando las vacías.
+#[derive(Debug)]
+pub struct MatchIter<'m, 't> {
+    matcher: &'m Matcher,
+    line: &'t [u8],
+    pos: usize,
+}
+
+impl<'m, 't> MatchIter<'m, 't> {
+    /// Crea un iterador sobre las coincidencias de `line`.
+    ///
+    /// # Arguments
+    ///
+    /// * `matcher` - El `Matcher` con el que se buscan las coincidencias.
+    /// * `line` - La línea en la que se busca, como texto o como bytes.
+    pub fn new<T: AsRef<[u8]> + ?Sized>(matcher: &'m Matcher, line: &'t T) -> MatchIter<'m, 't> {
+        MatchIter {
+            matcher,
+            line: line.as_ref(),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for MatchIter<'_, '_> {
+    type Item = (usize, usize);
+
+    /// Devuelve el inicio y el fin, en bytes, de la siguiente coincidencia no vacía.
+    fn next(&mut self) -> Option<(usize, usize)> {
+        while self.pos <= self.line.len() {
+            let (start, end) = self.matcher.find_at(self.line, self.pos)?;
+            if start < end {
+                self.pos = end;
+                return Some((start, end));
+            }
+            self.pos = match utf8::decode(&self.line[start..]) {
+                Some((_, size)) => start + size,
+                None => return None,
+            };
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grep_error::GrepError, grep_options::GrepOptions, regex_set::RegexSet};
+
+    fn matches(pattern: &str, line: &str) -> Result<Vec<(usize, usize)>, GrepError> {
+        let matcher = Matcher::new(RegexSet::new(&[pattern])?, &GrepOptions::default());
+        Ok(MatchIter::new(&matcher, line).collect())
+    }
+
+    #[test]
+    fn test_iter_all_patterns() -> Result<(), GrepError> {
+        assert_eq!(
+            matches("apple|melon", "melon apple melon")?,
+            vec![(0, 5), (6, 11), (12, 17)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_skips_empty_matches() -> Result<(), GrepError> {
+        assert_eq!(matches("a*", "baab")?, vec![(1, 3)]);
+        assert_eq!(matches("x?", "ñandú")?, vec![]);
+        Ok(())
+    }
+}
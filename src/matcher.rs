@@ -0,0 +1,205 @@
+use crate::{fixed_strings::FixedStrings, grep_options::GrepOptions, regex_set::RegexSet, utf8};
+
+/// Reúne los patrones en un `RegexSet`, o los textos fijos de `-F`, junto con las
+/// restricciones de `-x` y `-w`.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    regex_set: RegexSet,
+    fixed_strings: Option<FixedStrings>,
+    line_regexp: bool,
+    word_regexp: bool,
+}
+
+impl Matcher {
+    /// Crea un `Matcher` a partir de los patrones compilados y las opciones.
+    ///
+    /// # Arguments
+    ///
+    /// * `regex_set` - Los patrones; una línea coincide si coincide con alguno.
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    pub fn new(regex_set: RegexSet, options: &GrepOptions) -> Matcher {
+        Matcher {
+            regex_set,
+            fixed_strings: None,
+            line_regexp: options.line_regexp,
+            word_regexp: options.word_regexp,
+        }
+    }
+
+    /// Crea un `Matcher` que busca textos fijos, sin pasar por las expresiones regulares.
+    ///
+    /// # Arguments
+    ///
+    /// * `fixed_strings` - Los textos a buscar; una línea coincide si contiene alguno.
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    pub fn with_fixed_strings(fixed_strings: FixedStrings, options: &GrepOptions) -> Matcher {
+        Matcher {
+            regex_set: RegexSet::from_regexes(Vec::new()),
+            fixed_strings: Some(fixed_strings),
+            line_regexp: options.line_regexp,
+            word_regexp: options.word_regexp,
+        }
+    }
+
+    /// Indica si la línea tiene alguna coincidencia aceptada.
+    ///
+    /// Sin `-x` ni `-w` basta con una pasada del `RegexSet` por la línea. La línea puede ser
+    /// texto o bytes que no son UTF-8 válido.
+    pub fn is_match<T: AsRef<[u8]> + ?Sized>(&self, line: &T) -> bool {
+        let line = line.as_ref();
+        if self.fixed_strings.is_none() && !self.line_regexp && !self.word_regexp {
+            return self.regex_set.is_match(line);
+        }
+        self.find_at(line, 0).is_some()
+    }
+
+    /// Busca la primera coincidencia aceptada que empieza en `start` o después.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La línea en la que se busca.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin de la coincidencia que empieza más a la izquierda entre todos
+    /// los patrones; si empiezan en el mismo lugar, la más larga.
+    pub fn find_at<T: AsRef<[u8]> + ?Sized>(
+        &self,
+        line: &T,
+        start: usize,
+    ) -> Option<(usize, usize)> {
+        let line = line.as_ref();
+        let accept = |begin, end| self.accepts(line, begin, end);
+        match &self.fixed_strings {
+            Some(fixed_strings) => fixed_strings.find_where_bytes(line, start, &accept),
+            None => self.regex_set.find_where_bytes(line, start, &accept),
+        }
+    }
+
+    /// Indica si una coincidencia cumple con `-x` y `-w`.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La línea en la que se encontró la coincidencia.
+    /// * `start` - El inicio de la coincidencia, en bytes.
+    /// * `end` - El fin de la coincidencia, en bytes.
+    fn accepts(&self, line: &[u8], start: usize, end: usize) -> bool {
+        if self.line_regexp && (start != 0 || end != line.len()) {
+            return false;
+        }
+        if self.word_regexp {
+            let before = utf8::decode_last(&line[..start]);
+            let after = utf8::decode(&line[end..]);
+            return !before.is_some_and(|(c, _)| Matcher::is_word_char(c))
+                && !after.is_some_and(|(c, _)| Matcher::is_word_char(c));
+        }
+        true
+    }
+
+    /// Indica si el caracter forma parte de una palabra: letras, dígitos y `_`.
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grep_error::GrepError;
+
+    fn matcher(pattern: &str, options: &GrepOptions) -> Result<Matcher, GrepError> {
+        Ok(Matcher::new(RegexSet::new(&[pattern])?, options))
+    }
+
+    #[test]
+    fn test_invalid_utf8() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            word_regexp: true,
+            ..GrepOptions::default()
+        };
+        let matcher = matcher("caf.", &options)?;
+        assert!(matcher.is_match(b"un caf\xe9 con leche"));
+        assert!(!matcher.is_match(b"un caf\xe9s"));
+        assert_eq!(matcher.find_at(b"\xff caf\xe9", 0), Some((2, 6)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Matcher>();
+    }
+
+    #[test]
+    fn test_find_leftmost_pattern() -> Result<(), GrepError> {
+        let matcher = matcher("melon|apple", &GrepOptions::default())?;
+        assert_eq!(matcher.find_at("apple melon", 0), Some((0, 5)));
+        assert_eq!(matcher.find_at("apple melon", 1), Some((6, 11)));
+        assert_eq!(matcher.find_at("kiwi", 0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_regexp() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            line_regexp: true,
+            ..GrepOptions::default()
+        };
+        let matcher = matcher("ab*|kiwi", &options)?;
+        assert!(matcher.is_match("abbb"));
+        assert!(matcher.is_match("kiwi"));
+        assert!(!matcher.is_match("abbbc"));
+        assert!(!matcher.is_match("un kiwi"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_regexp() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            word_regexp: true,
+            ..GrepOptions::default()
+        };
+        let matcher = matcher("melon", &options)?;
+        assert!(matcher.is_match("soy melon"));
+        assert!(matcher.is_match("melon, kiwi"));
+        assert!(!matcher.is_match("watermelon"));
+        assert!(!matcher.is_match("melon_2"));
+        assert_eq!(matcher.find_at("watermelon melon", 0), Some((11, 16)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixed_strings() {
+        let options = GrepOptions {
+            word_regexp: true,
+            ..GrepOptions::default()
+        };
+        let needles = vec!["a.b".to_string(), "a.b[0]".to_string()];
+        let matcher = Matcher::with_fixed_strings(FixedStrings::new(&needles, false), &options);
+        assert!(matcher.is_match("x = a.b[0];"));
+        assert!(!matcher.is_match("xa.b[0]"));
+        assert!(!matcher.is_match("axb"));
+        assert_eq!(matcher.find_at("a.bc a.b_x a.b", 0), Some((11, 14)));
+
+        let options = GrepOptions {
+            line_regexp: true,
+            ..GrepOptions::default()
+        };
+        let matcher = Matcher::with_fixed_strings(FixedStrings::new(&needles, true), &options);
+        assert!(matcher.is_match("A.B[0]"));
+        assert!(!matcher.is_match(" a.b"));
+    }
+
+    #[test]
+    fn test_word_regexp_tries_shorter_match() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            word_regexp: true,
+            ..GrepOptions::default()
+        };
+        let matcher = matcher("ab.?", &options)?;
+        assert_eq!(matcher.find_at("ab c", 0), Some((0, 2)));
+        assert!(!matcher.is_match("abbc"));
+        Ok(())
+    }
+}
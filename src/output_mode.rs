@@ -0,0 +1,20 @@
+/// Representa qué se imprime por cada entrada: las líneas seleccionadas o sólo un resumen.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    #[default]
+    Lines,
+    Count,
+    FilesWithMatches,
+    FilesWithoutMatch,
+    Quiet,
+}
+
+impl OutputMode {
+    /// Indica si alcanza con encontrar la primera línea seleccionada para dejar de leer la entrada.
+    pub fn stops_at_first(&self) -> bool {
+        matches!(
+            self,
+            OutputMode::FilesWithMatches | OutputMode::FilesWithoutMatch | OutputMode::Quiet
+        )
+    }
+}
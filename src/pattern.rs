@@ -0,0 +1,211 @@
+use std::io::Read;
+
+use crate::{
+    dialect::Dialect, grep_error::GrepError, grep_input::GrepInput, grep_options::GrepOptions,
+    regex::Regex, regex_flags::RegexFlags, regex_set::RegexSet,
+};
+
+/// Representa un patrón junto con el archivo y la línea de donde se leyó con `-f`, para poder
+/// informar dónde está si no es válido.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub text: String,
+    pub origin: Option<(String, usize)>,
+}
+
+impl Pattern {
+    /// Reúne los patrones pasados con `-e` o como primer operando y los leídos de los
+    /// archivos de `-f`. Un patrón con saltos de línea son varios patrones, uno por línea.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    ///
+    /// # Returns
+    ///
+    /// Si algún archivo de patrones no se puede leer, devuelve un error de tipo `GrepError`.
+    pub fn from_options(options: &GrepOptions) -> Result<Vec<Pattern>, GrepError> {
+        let mut patterns: Vec<Pattern> = options
+            .patterns
+            .iter()
+            .flat_map(|text| text.split('\n'))
+            .map(|text| Pattern {
+                text: text.to_string(),
+                origin: None,
+            })
+            .collect();
+        for name in &options.pattern_files {
+            patterns.extend(Pattern::read_file(name)?);
+        }
+        Ok(patterns)
+    }
+
+    /// Lee un patrón por línea de un archivo, o de la entrada estándar si el nombre es `-`.
+    ///
+    /// Una línea vacía es un patrón vacío, que coincide con todas las líneas; un archivo vacío
+    /// no tiene patrones. Se quita el `\r` del final de las líneas terminadas en `\r\n`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre del archivo, o `-` para la entrada estándar.
+    ///
+    /// # Returns
+    ///
+    /// Si el archivo no se puede leer, devuelve un error de tipo `GrepError`.
+    pub fn read_file(name: &str) -> Result<Vec<Pattern>, GrepError> {
+        let input = GrepInput::from_operand(name);
+        let mut bytes = Vec::new();
+        input
+            .open()
+            .and_then(|mut reader| {
+                reader
+                    .read_to_end(&mut bytes)
+                    .map_err(|_| GrepError::ErrArchivo)
+            })
+            .map_err(|_| GrepError::ErrArchivoPatrones(name.to_string()))?;
+
+        if bytes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        Ok(text
+            .split('\n')
+            .enumerate()
+            .map(|(i, line)| Pattern {
+                text: line.strip_suffix('\r').unwrap_or(line).to_string(),
+                origin: Some((input.name().to_string(), i + 1)),
+            })
+            .collect())
+    }
+
+    /// Compila las alternativas del patrón.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - Los modificadores con los que empieza cada alternativa, como los de `-i`.
+    /// * `dialect` - La sintaxis en la que está escrito el patrón.
+    ///
+    /// # Returns
+    ///
+    /// Si el patrón no es válido, devuelve un error de tipo `GrepError`, que indica el archivo
+    /// y la línea si se leyó con `-f` junto con el motivo.
+    pub fn compile(&self, flags: RegexFlags, dialect: Dialect) -> Result<Vec<Regex>, GrepError> {
+        Regex::crear_regex_con_dialecto(&self.text, flags, dialect).map_err(|err| {
+            match &self.origin {
+                Some((name, line)) => {
+                    GrepError::ErrPatron(format!("{}:{}", name, line), Box::new(err))
+                }
+                None => err,
+            }
+        })
+    }
+
+    /// Compila todos los patrones en un solo `RegexSet`, que los prueba a la vez en cada línea
+    /// en lugar de probar cada `Regex` por separado.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Los patrones de `-e` y de `-f`, en orden.
+    /// * `flags` - Los modificadores con los que empieza cada alternativa, como los de `-i`.
+    /// * `dialect` - La sintaxis en la que están escritos los patrones.
+    ///
+    /// # Returns
+    ///
+    /// Si algún patrón no es válido, devuelve un error de tipo `GrepError`, que indica el
+    /// archivo y la línea si se leyó con `-f`.
+    pub fn compile_set(
+        patterns: &[Pattern],
+        flags: RegexFlags,
+        dialect: Dialect,
+    ) -> Result<RegexSet, GrepError> {
+        let groups = patterns
+            .iter()
+            .map(|pattern| pattern.compile(flags, dialect))
+            .collect::<Result<Vec<Vec<Regex>>, GrepError>>()?;
+        Ok(RegexSet::from_regexes(groups))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test_files::write_temp;
+
+    #[test]
+    fn test_read_file() -> Result<(), GrepError> {
+        let name = write_temp("patrones", b"kiwi\r\n\nmel.n\n")?;
+        let patterns = Pattern::read_file(&name)?;
+        let texts: Vec<&str> = patterns
+            .iter()
+            .map(|pattern| pattern.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["kiwi", "", "mel.n"]);
+        assert_eq!(patterns[2].origin, Some((name.clone(), 3)));
+        let everything = patterns[1].compile(RegexFlags::default(), Dialect::Extended)?;
+        assert!(everything[0].test("kiwi")?);
+
+        let empty = write_temp("vacio", b"")?;
+        assert!(Pattern::read_file(&empty)?.is_empty());
+
+        let _ = fs::remove_file(&name);
+        let _ = fs::remove_file(&empty);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_set() -> Result<(), GrepError> {
+        let name = write_temp("conjunto", b"^ban\nmel.n$\n")?;
+        let mut patterns = vec![Pattern {
+            text: "kiwi".to_string(),
+            origin: None,
+        }];
+        patterns.extend(Pattern::read_file(&name)?);
+        let set = Pattern::compile_set(&patterns, RegexFlags::default(), Dialect::Extended)?;
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.matches("banana y melon"), vec![1, 2]);
+
+        let _ = fs::remove_file(&name);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let result = Pattern::read_file("no_existe.txt");
+        assert!(
+            matches!(result, Err(GrepError::ErrArchivoPatrones(name)) if name == "no_existe.txt")
+        );
+    }
+
+    #[test]
+    fn test_compile_reports_origin() {
+        let pattern = Pattern {
+            text: "a{x}".to_string(),
+            origin: Some(("lista.txt".to_string(), 4)),
+        };
+        let result = pattern.compile(RegexFlags::default(), Dialect::Extended);
+        assert!(matches!(
+            result,
+            Err(GrepError::ErrPatron(origin, cause))
+                if origin == "lista.txt:4" && matches!(*cause, GrepError::Err)
+        ));
+        assert_eq!(
+            GrepError::ErrPatron("lista.txt:4".to_string(), Box::new(GrepError::Err)).to_string(),
+            "lista.txt:4: Expresión regular inválida"
+        );
+        let cause = GrepError::ErrRegex("falta un `)`".to_string());
+        assert_eq!(
+            GrepError::ErrPatron("lista.txt:4".to_string(), Box::new(cause)).to_string(),
+            "lista.txt:4: Expresión regular inválida: falta un `)`"
+        );
+
+        let pattern = Pattern {
+            text: "a{x}".to_string(),
+            origin: None,
+        };
+        let result = pattern.compile(RegexFlags::default(), Dialect::Extended);
+        assert!(matches!(result, Err(GrepError::Err)));
+    }
+}
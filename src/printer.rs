@@ -0,0 +1,568 @@
+use std::io::Write;
+
+use crate::{
+    color_choice::ColorChoice, colors::Colors, grep_error::GrepError, grep_options::GrepOptions,
+    match_iter::MatchIter, matcher::Matcher, search_line::SearchLine, utf8,
+};
+
+/// Escribe los resultados de la búsqueda a medida que se encuentran.
+#[derive(Debug)]
+pub struct Printer<W: Write> {
+    out: W,
+    with_filename: bool,
+    line_number: bool,
+    column: bool,
+    column_in_chars: bool,
+    byte_offset: bool,
+    only_matching: bool,
+    invert_match: bool,
+    colors: Option<Colors>,
+    terminator: u8,
+    record_terminator: Vec<u8>,
+    null: bool,
+    printed: bool,
+}
+
+impl<W: Write> Printer<W> {
+    /// Crea un `Printer` que escribe en `out`, configurado según las opciones.
+    ///
+    /// Sólo colorea la salida si `--color` quedó en `ColorChoice::Always`; `ColorChoice::Auto`
+    /// tiene que resolverse antes según a dónde se escriba.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - El destino donde se escriben los resultados.
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    pub fn new(out: W, options: &GrepOptions) -> Printer<W> {
+        Printer {
+            out,
+            with_filename: options.show_filename(false),
+            line_number: options.line_number,
+            column: options.column,
+            column_in_chars: options.column_in_chars,
+            byte_offset: options.byte_offset,
+            only_matching: options.only_matching,
+            invert_match: options.invert_match,
+            colors: (options.color == ColorChoice::Always).then(|| options.colors.clone()),
+            terminator: options.line_terminator(),
+            record_terminator: match &options.record_separator {
+                Some(separator) => separator.clone(),
+                None => vec![options.line_terminator()],
+            },
+            null: options.null,
+            printed: false,
+        }
+    }
+
+    /// Cambia si se antepone el nombre del archivo a cada línea, una vez que se sabe si el
+    /// recorrido de las entradas entró en algún directorio.
+    ///
+    /// # Arguments
+    ///
+    /// * `with_filename` - Si hay que anteponer el nombre del archivo.
+    pub fn set_with_filename(&mut self, with_filename: bool) {
+        self.with_filename = with_filename;
+    }
+
+    /// Imprime una línea seleccionada, con el prefijo `archivo:línea:columna:offset:` que corresponda.
+    ///
+    /// Con `-o` imprime en cambio cada coincidencia no vacía en su propia línea, con el offset
+    /// y la columna de la coincidencia.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada de la que sale la línea.
+    /// * `line` - La línea, sin el terminador, y su posición en la entrada.
+    /// * `matcher` - El `Matcher` con el que se buscan las coincidencias, para la columna y el color.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_line(
+        &mut self,
+        name: &str,
+        line: &SearchLine,
+        matcher: &Matcher,
+    ) -> Result<(), GrepError> {
+        if self.only_matching {
+            return self.print_matches(name, line, matcher);
+        }
+        let column = if self.column {
+            matcher
+                .find_at(line.text, 0)
+                .map(|(start, _)| self.column_of(line.text, start))
+        } else {
+            None
+        };
+        let spans = self.spans(line.text, matcher, !self.invert_match);
+        self.write_line(name, line, true, column, &spans)
+    }
+
+    /// Imprime cada coincidencia no vacía de la línea en su propia línea, para `-o`.
+    fn print_matches(
+        &mut self,
+        name: &str,
+        line: &SearchLine,
+        matcher: &Matcher,
+    ) -> Result<(), GrepError> {
+        for (start, end) in MatchIter::new(matcher, line.text) {
+            let part = SearchLine {
+                number: line.number,
+                offset: line.offset + start,
+                text: &line.text[start..end],
+            };
+            let column = self.column.then(|| self.column_of(line.text, start));
+            self.write_line(name, &part, true, column, &[(0, end - start)])?;
+        }
+        Ok(())
+    }
+
+    /// Imprime una línea de contexto, usando `-` en lugar de `:` después de cada prefijo.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada de la que sale la línea.
+    /// * `line` - La línea, sin el terminador, y su posición en la entrada.
+    /// * `matcher` - El `Matcher` con el que se buscan las coincidencias para colorearlas con `-v`.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_context(
+        &mut self,
+        name: &str,
+        line: &SearchLine,
+        matcher: &Matcher,
+    ) -> Result<(), GrepError> {
+        let spans = self.spans(line.text, matcher, self.invert_match);
+        self.write_line(name, line, false, None, &spans)
+    }
+
+    /// Imprime el separador `--` entre dos grupos de líneas que no son contiguos.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_group_separator(&mut self) -> Result<(), GrepError> {
+        let mut separator = self.paint(|colors| &colors.separator, b"--");
+        separator.push(self.terminator);
+        self.out.write_all(&separator).map_err(|_| GrepError::Err)
+    }
+
+    /// Imprime la cantidad de líneas seleccionadas de una entrada, para `-c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada.
+    /// * `count` - La cantidad de líneas seleccionadas.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_count(&mut self, name: &str, count: usize) -> Result<(), GrepError> {
+        let mut output = Vec::new();
+        if self.with_filename {
+            let separator = self.paint(|colors| &colors.separator, b":");
+            self.push_filename(&mut output, name, &separator);
+        }
+        output.extend(format!("{}\n", count).into_bytes());
+        self.out.write_all(&output).map_err(|_| GrepError::Err)
+    }
+
+    /// Imprime el nombre de una entrada, para `-l` y `-L`, terminado en `\n` o, con `-Z`, en NUL.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_filename(&mut self, name: &str) -> Result<(), GrepError> {
+        let mut output = self.paint(|colors| &colors.filename, name.as_bytes());
+        output.push(if self.null { b'\0' } else { b'\n' });
+        self.out.write_all(&output).map_err(|_| GrepError::Err)
+    }
+
+    /// Escribe la salida ya formateada de una entrada en la que se buscó desde otro hilo.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Lo que imprimió el `Printer` de ese hilo.
+    /// * `separate` - Si hay que anteponer `--` cuando ya se imprimió alguna línea, porque se
+    ///   pidió contexto.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_chunk(&mut self, output: &[u8], separate: bool) -> Result<(), GrepError> {
+        if output.is_empty() {
+            return Ok(());
+        }
+        if separate && self.printed {
+            self.print_group_separator()?;
+        }
+        self.printed = true;
+        self.out.write_all(output).map_err(|_| GrepError::Err)
+    }
+
+    /// Informa que una entrada binaria tiene alguna línea seleccionada, en lugar de imprimirla.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al escribir, devuelve un error de tipo `GrepError`.
+    pub fn print_binary_match(&mut self, name: &str) -> Result<(), GrepError> {
+        writeln!(self.out, "Binary file {} matches", name).map_err(|_| GrepError::Err)
+    }
+
+    /// Indica si ya se imprimió alguna línea, de cualquier entrada.
+    pub fn has_printed(&self) -> bool {
+        self.printed
+    }
+
+    /// Devuelve el destino en el que escribe el `Printer`.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Escribe una línea con sus prefijos, separados por `:` si es una línea seleccionada
+    /// o por `-` si es de contexto.
+    ///
+    /// Con `--record-separator` cada registro termina con el separador, para conservar el
+    /// formato de la entrada; las coincidencias de `-o` terminan siempre en una línea.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - El nombre de la entrada de la que sale la línea.
+    /// * `line` - La línea, sin el terminador, y su posición en la entrada.
+    /// * `selected` - Si la línea fue seleccionada o es de contexto.
+    /// * `column` - La columna de la primera coincidencia, si hay que imprimirla.
+    /// * `spans` - Las coincidencias a resaltar dentro de la línea, si se colorea.
+    fn write_line(
+        &mut self,
+        name: &str,
+        line: &SearchLine,
+        selected: bool,
+        column: Option<usize>,
+        spans: &[(usize, usize)],
+    ) -> Result<(), GrepError> {
+        let separator = self.paint(
+            |colors| &colors.separator,
+            if selected { b":" } else { b"-" },
+        );
+        let mut output = Vec::new();
+
+        if self.with_filename {
+            self.push_filename(&mut output, name, &separator);
+        }
+        if self.line_number {
+            let number = line.number.to_string();
+            output.extend(self.paint(|colors| &colors.line_number, number.as_bytes()));
+            output.extend_from_slice(&separator);
+        }
+        if let Some(column) = column {
+            let column = column.to_string();
+            output.extend(self.paint(|colors| &colors.line_number, column.as_bytes()));
+            output.extend_from_slice(&separator);
+        }
+        if self.byte_offset {
+            let offset = line.offset.to_string();
+            output.extend(self.paint(|colors| &colors.byte_offset, offset.as_bytes()));
+            output.extend_from_slice(&separator);
+        }
+
+        self.paint_text(&mut output, line.text, selected, spans);
+        if self.only_matching {
+            output.push(self.terminator);
+        } else {
+            output.extend_from_slice(&self.record_terminator);
+        }
+        self.printed = true;
+        self.out.write_all(&output).map_err(|_| GrepError::Err)
+    }
+
+    /// Agrega el nombre de una entrada como prefijo, seguido del separador o, con `-Z`, de un NUL.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - La salida a la que se agrega el prefijo.
+    /// * `name` - El nombre de la entrada.
+    /// * `separator` - El separador, ya coloreado si corresponde, que sigue al nombre sin `-Z`.
+    fn push_filename(&self, output: &mut Vec<u8>, name: &str, separator: &[u8]) {
+        output.extend(self.paint(|colors| &colors.filename, name.as_bytes()));
+        if self.null {
+            output.push(b'\0');
+        } else {
+            output.extend_from_slice(separator);
+        }
+    }
+
+    /// Agrega el texto de una línea a la salida, coloreado si corresponde, resaltando las
+    /// coincidencias sobre el color de la línea.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - Donde se agrega el texto.
+    /// * `text` - El texto de la línea.
+    /// * `selected` - Si la línea fue seleccionada o es de contexto.
+    /// * `spans` - Las coincidencias a resaltar, ordenadas y sin superponerse.
+    fn paint_text(
+        &self,
+        output: &mut Vec<u8>,
+        text: &[u8],
+        selected: bool,
+        spans: &[(usize, usize)],
+    ) {
+        let colors = match &self.colors {
+            Some(colors) => colors,
+            None => return output.extend_from_slice(text),
+        };
+        let (line_sgr, match_sgr) = if selected {
+            (&colors.selected_line, &colors.selected_match)
+        } else {
+            (&colors.context_line, &colors.context_match)
+        };
+
+        let mut pos = 0;
+        for (start, end) in spans {
+            output.extend(colors.paint(line_sgr, &text[pos..*start]));
+            output.extend(colors.paint(match_sgr, &text[*start..*end]));
+            pos = *end;
+        }
+        output.extend(colors.paint(line_sgr, &text[pos..]));
+    }
+
+    /// Colorea un texto con la secuencia que `sgr` elige de los colores, si se colorea la salida.
+    fn paint(&self, sgr: fn(&Colors) -> &String, text: &[u8]) -> Vec<u8> {
+        match &self.colors {
+            Some(colors) => colors.paint(sgr(colors), text),
+            None => text.to_vec(),
+        }
+    }
+
+    /// Devuelve las coincidencias de la línea a resaltar, si se colorea la salida y `highlight`.
+    fn spans(&self, text: &[u8], matcher: &Matcher, highlight: bool) -> Vec<(usize, usize)> {
+        if self.colors.is_some() && highlight {
+            MatchIter::new(matcher, text).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Calcula la columna, empezando en 1, de una coincidencia de la línea.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - El texto de la línea.
+    /// * `start` - El inicio de la coincidencia, en bytes.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la columna contada en bytes, o en caracteres si así se configuró.
+    fn column_of(&self, text: &[u8], start: usize) -> usize {
+        if self.column_in_chars {
+            utf8::count_chars(&text[..start]) + 1
+        } else {
+            start + 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex_set::RegexSet;
+
+    fn print(options: &GrepOptions, pattern: &str, text: &str) -> Result<String, GrepError> {
+        let matcher = Matcher::new(RegexSet::new(&[pattern])?, options);
+        let mut printer = Printer::new(Vec::new(), options);
+        let line = SearchLine {
+            number: 3,
+            offset: 42,
+            text: text.as_bytes(),
+        };
+        printer.print_line("a.txt", &line, &matcher)?;
+        Ok(String::from_utf8(printer.into_inner()).unwrap_or_default())
+    }
+
+    #[test]
+    fn test_print_line() -> Result<(), GrepError> {
+        let out = print(&GrepOptions::default(), "ol", "hola")?;
+        assert_eq!(out, "hola\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_line_with_filename() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            with_filename: Some(true),
+            ..GrepOptions::default()
+        };
+        let out = print(&options, "ol", "hola")?;
+        assert_eq!(out, "a.txt:hola\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_line_all_prefixes() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            with_filename: Some(true),
+            line_number: true,
+            column: true,
+            byte_offset: true,
+            ..GrepOptions::default()
+        };
+        let out = print(&options, "ol", "hola")?;
+        assert_eq!(out, "a.txt:3:2:42:hola\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_only_matching() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            only_matching: true,
+            with_filename: Some(true),
+            line_number: true,
+            byte_offset: true,
+            ..GrepOptions::default()
+        };
+        let out = print(&options, "ab*|c", "xabbycab")?;
+        assert_eq!(out, "a.txt:3:43:abb\na.txt:3:47:c\na.txt:3:48:ab\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_context() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            with_filename: Some(true),
+            line_number: true,
+            column: true,
+            ..GrepOptions::default()
+        };
+        let matcher = Matcher::new(RegexSet::new(&["ol"])?, &options);
+        let mut printer = Printer::new(Vec::new(), &options);
+        assert!(!printer.has_printed());
+        let line = SearchLine {
+            number: 7,
+            offset: 0,
+            text: b"hola",
+        };
+        printer.print_context("a.txt", &line, &matcher)?;
+        printer.print_group_separator()?;
+        assert!(printer.has_printed());
+        assert_eq!(printer.into_inner(), b"a.txt-7-hola\n--\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_count_and_filename() -> Result<(), GrepError> {
+        let mut printer = Printer::new(Vec::new(), &GrepOptions::default());
+        printer.print_count("a.txt", 3)?;
+        printer.print_filename("a.txt")?;
+        assert_eq!(printer.into_inner(), b"3\na.txt\n");
+
+        let options = GrepOptions {
+            with_filename: Some(true),
+            ..GrepOptions::default()
+        };
+        let mut printer = Printer::new(Vec::new(), &options);
+        printer.print_count("a.txt", 3)?;
+        assert_eq!(printer.into_inner(), b"a.txt:3\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_null() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            with_filename: Some(true),
+            line_number: true,
+            null: true,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "ol", "hola")?, "a.txt\x003:hola\n");
+
+        let mut printer = Printer::new(Vec::new(), &options);
+        printer.print_filename("a.txt")?;
+        printer.print_count("b.txt", 3)?;
+        assert_eq!(printer.into_inner(), b"a.txt\0b.txt\x003\n");
+
+        let options = GrepOptions {
+            null_data: true,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "ol", "hola\nchau")?, "hola\nchau\0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_line_colored() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            color: ColorChoice::Always,
+            with_filename: Some(true),
+            line_number: true,
+            ..GrepOptions::default()
+        };
+        let out = print(&options, "o", "hola mundo")?;
+        assert_eq!(
+            out,
+            "\x1b[35m\x1b[Ka.txt\x1b[m\x1b[K\x1b[36m\x1b[K:\x1b[m\x1b[K\
+             \x1b[32m\x1b[K3\x1b[m\x1b[K\x1b[36m\x1b[K:\x1b[m\x1b[K\
+             h\x1b[01;31m\x1b[Ko\x1b[m\x1b[Kla mund\x1b[01;31m\x1b[Ko\x1b[m\x1b[K\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_line_custom_colors() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            color: ColorChoice::Always,
+            colors: Colors::parse("ms=4:sl=1:ne"),
+            ..GrepOptions::default()
+        };
+        let out = print(&options, "ol", "hola")?;
+        assert_eq!(out, "\x1b[1mh\x1b[m\x1b[4mol\x1b[m\x1b[1ma\x1b[m\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_line_color_never() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            color: ColorChoice::Auto,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "ol", "hola")?, "hola\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_bytes_and_chars() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            column: true,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "x", "ñandú x")?, "9:ñandú x\n");
+
+        let options = GrepOptions {
+            column: true,
+            column_in_chars: true,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "x", "ñandú x")?, "7:ñandú x\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_without_match() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            column: true,
+            line_number: true,
+            ..GrepOptions::default()
+        };
+        assert_eq!(print(&options, "x", "hola")?, "3:hola\n");
+        Ok(())
+    }
+}
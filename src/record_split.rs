@@ -0,0 +1,78 @@
+use crate::{
+    grep_error::GrepError, grep_options::GrepOptions, matcher::Matcher, regex::Regex,
+    regex_flags::RegexFlags, regex_set::RegexSet,
+};
+
+/// Representa cómo se divide la entrada en los registros contra los que se prueba el patrón.
+#[derive(Debug, Default, Clone)]
+pub enum RecordSplit {
+    /// Cada línea es un registro.
+    #[default]
+    Lines,
+    /// Los registros terminan con la cadena dada, según `--record-separator`.
+    Separator(Vec<u8>),
+    /// Cada línea que coincide con el patrón empieza un registro, según `--record-start`.
+    Start(Box<Matcher>),
+}
+
+impl RecordSplit {
+    /// Arma la división en registros pedida en las opciones.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    ///
+    /// # Returns
+    ///
+    /// Si el patrón de `--record-start` no es válido, devuelve un error de tipo `GrepError`.
+    pub fn new(options: &GrepOptions) -> Result<RecordSplit, GrepError> {
+        if let Some(pattern) = &options.record_start {
+            let regex_vec =
+                Regex::crear_regex_con_dialecto(pattern, RegexFlags::default(), options.dialect)?;
+            let regex_set = RegexSet::from_regexes(vec![regex_vec]);
+            let matcher = Matcher::new(regex_set, &GrepOptions::default());
+            return Ok(RecordSplit::Start(Box::new(matcher)));
+        }
+        Ok(match &options.record_separator {
+            Some(separator) => RecordSplit::Separator(separator.clone()),
+            None => RecordSplit::Lines,
+        })
+    }
+
+    /// Indica si los registros pueden ocupar más de una línea.
+    pub fn is_multiline(&self) -> bool {
+        !matches!(self, RecordSplit::Lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() -> Result<(), GrepError> {
+        assert!(!RecordSplit::new(&GrepOptions::default())?.is_multiline());
+
+        let options = GrepOptions {
+            record_separator: Some(b"\n\n".to_vec()),
+            ..GrepOptions::default()
+        };
+        assert!(matches!(
+            RecordSplit::new(&options)?,
+            RecordSplit::Separator(separator) if separator == b"\n\n"
+        ));
+
+        let options = GrepOptions {
+            record_start: Some("^[[:digit:]]+ ".to_string()),
+            ..GrepOptions::default()
+        };
+        assert!(RecordSplit::new(&options)?.is_multiline());
+
+        let options = GrepOptions {
+            record_start: Some("(".to_string()),
+            ..GrepOptions::default()
+        };
+        assert!(RecordSplit::new(&options).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+/// Representa si se recorren los directorios, según `-r` y `-R`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Recursion {
+    #[default]
+    Off,
+    /// `-r`: recorre los directorios sin seguir los enlaces simbólicos que encuentra.
+    Recursive,
+    /// `-R`: recorre los directorios siguiendo todos los enlaces simbólicos.
+    FollowLinks,
+}
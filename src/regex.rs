@@ -1,277 +1,760 @@
-use std::collections::VecDeque;
+use std::{mem, str::Chars};
 
 use crate::{
     anchoring::Anchoring, bracket_expression::BracketExpression, character_class::CharacterClass,
-    evaluated_step::EvaluatedStep, grep_error::GrepError, regex_rep::RegexRep,
-    regex_step::RegexStep, regex_value::RegexValue,
+    dialect::Dialect, escape, grep_error::GrepError, group_frame::GroupFrame,
+    regex_flags::RegexFlags, regex_rep::RegexRep, regex_step::RegexStep, regex_value::RegexValue,
+    utf8,
 };
 
 #[derive(Debug, Clone)]
 pub struct Regex {
     steps: Vec<RegexStep>,
     anchoring: Anchoring,
+    /// Si el `^` estaba dentro del alcance de `(?m)` y coincide también después de cada `\n`.
+    multi_line_start: bool,
+    /// Si el `$` estaba dentro del alcance de `(?m)` y coincide también antes de cada `\n`.
+    multi_line_end: bool,
 }
 
 impl Regex {
     pub fn new(expression: &str) -> Result<Self, GrepError> {
-        let mut steps: Vec<RegexStep> = vec![];
+        Regex::with_flags(expression, RegexFlags::default())
+    }
+
+    /// Compila una expresión regular empezando con los modificadores dados, como los de `-i`.
+    ///
+    /// Un grupo `(?flags)` cambia los modificadores desde ahí hasta el final del grupo que lo
+    /// contiene, y `(?flags:...)` sólo dentro de sus paréntesis. Cada paso se arma con los
+    /// modificadores vigentes: con `(?i)` los literales y las clases aceptan también la otra
+    /// mayúscula o minúscula, sin `(?s)` el `.` no acepta `\n`, y con `(?m)` los anclajes
+    /// coinciden en cada línea. Con `(?x)` se ignoran los espacios y los comentarios con `#`.
+    ///
+    /// Como cada anclaje guarda el `(?m)` con el que se leyó, en `(?m:^a)b$` el `^` coincide en
+    /// cada línea y el `$` sólo al final del texto.
+    ///
+    /// Un grupo `(...)` sin modificadores sólo agrupa, y se puede repetir como cualquier paso,
+    /// como en `(ab)*` o `(a|b)+`. Las alternativas con `|` fuera de los grupos se compilan
+    /// con `crear_regex`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - La expresión regular.
+    /// * `flags` - Los modificadores con los que empieza.
+    ///
+    /// # Returns
+    ///
+    /// Si la expresión no es válida, devuelve un error de tipo `GrepError`.
+    pub fn with_flags(expression: &str, flags: RegexFlags) -> Result<Self, GrepError> {
+        let mut alternatives = Regex::parse(expression, flags, Dialect::Extended)?;
+        if alternatives.len() > 1 {
+            return Err(GrepError::ErrRegex(
+                "las alternativas con `|` se compilan con `crear_regex`".to_string(),
+            ));
+        }
+        Ok(Regex::from_steps(alternatives.pop().unwrap_or_default()))
+    }
+
+    /// Lee una expresión regular escrita en el dialecto dado.
+    ///
+    /// Los dos dialectos producen los mismos pasos; sólo cambia qué caracteres son operadores,
+    /// según `Dialect::is_operator`. En la sintaxis básica también son literales el `*` del
+    /// comienzo del patrón, de un grupo o de una alternativa, el `^` que no está en esos
+    /// lugares y el `$` que no está al final de ellos.
+    ///
+    /// Al pasar a otra alternativa vuelven a valer los modificadores del comienzo del grupo.
+    /// Los grupos de modificadores del comienzo del patrón, como `(?i)`, valen para todas las
+    /// alternativas.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - La expresión regular.
+    /// * `flags` - Los modificadores con los que empieza.
+    /// * `dialect` - La sintaxis en la que está escrita.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve los pasos de cada alternativa que no está dentro de un grupo.
+    ///
+    /// Si la expresión no es válida, devuelve un error de tipo `GrepError`.
+    fn parse(
+        expression: &str,
+        flags: RegexFlags,
+        dialect: Dialect,
+    ) -> Result<Vec<Vec<RegexStep>>, GrepError> {
+        let mut flags = flags;
+        let mut frames = vec![GroupFrame::new(flags, flags)];
         let mut chars_iter = expression.chars();
-        let mut anchoring = Anchoring::new();
+        let mut prefix = true;
+        let mut at_start = true;
 
         while let Some(c) = chars_iter.next() {
-            println!("Procesando carácter: {}", c);
-            let step = match c {
-                '.' => Some(RegexStep {
-                    rep: RegexRep::Exact(1),
-                    val: RegexValue::Wildcard,
-                }),
-                'a'..='z' | 'A'..='Z' | '0'..='9' | ' ' => Some(RegexStep {
-                    rep: RegexRep::Exact(1),
-                    val: RegexValue::Literal(c),
-                }),
-                '*' => {
-                    if let Some(last) = steps.last_mut() {
-                        println!("Encontrado '*', modificando el último paso: {:?}", last);
-                        last.rep = RegexRep::Any;
+            if flags.extended && c.is_whitespace() {
+                continue;
+            }
+            if flags.extended && c == '#' {
+                chars_iter.by_ref().find(|&c| c == '\n');
+                continue;
+            }
+            let starts = mem::replace(&mut at_start, false);
+            let operator = Regex::read_operator(c, &mut chars_iter, dialect);
+            let top_level = frames.len() == 1;
+            let frame = frames.last_mut().ok_or(GrepError::Err)?;
+
+            let step = match (c, operator) {
+                (_, Some('(')) if chars_iter.clone().next() == Some('?') => {
+                    chars_iter.next();
+                    let (letters, scoped) = Regex::read_flag_letters(&mut chars_iter, dialect)?;
+                    let group_flags = flags.apply(&letters)?;
+                    if scoped {
+                        frames.push(GroupFrame::new(group_flags, flags));
+                        at_start = true;
                     } else {
-                        return Err(GrepError::Err);
+                        if prefix && top_level {
+                            frame.flags = group_flags;
+                        }
+                        at_start = starts;
                     }
+                    flags = group_flags;
+                    prefix &= !scoped;
+                    continue;
+                }
+                (_, Some('(')) => {
+                    frames.push(GroupFrame::new(flags, flags));
+                    at_start = true;
                     None
                 }
-                '^' => {
-                    if steps.is_empty() {
-                        anchoring.update_anchoring('^');
-                    } else {
+                (_, Some(')')) => {
+                    if top_level {
                         return Err(GrepError::Err);
                     }
+                    let group = frames.pop().ok_or(GrepError::Err)?;
+                    flags = group.outer_flags;
+                    Some(RegexStep {
+                        rep: RegexRep::Exact(1),
+                        val: RegexValue::Grupo(group.finish()),
+                    })
+                }
+                (_, Some('|')) => {
+                    frame.next_alternative();
+                    flags = frame.flags;
+                    at_start = true;
                     None
                 }
-                '$' => {
-                    if chars_iter.clone().next().is_none() {
-                        anchoring.update_anchoring('$');
-                    } else {
-                        return Err(GrepError::Err);
-                    }
+                (_, Some('{')) => {
+                    Regex::read_interval(&mut chars_iter, dialect, &mut frame.steps)?;
                     None
                 }
-                '+' => {
-                    if let Some(last) = steps.last_mut() {
-                        println!("Encontrado '+', modificando el último paso: {:?}", last);
-                        match last.rep {
-                            RegexRep::Exact(n) => {
+                (_, Some('+')) => {
+                    let last = Regex::last_repeatable(&mut frame.steps)?;
+                    match last.rep {
+                        RegexRep::Exact(n) => {
+                            last.rep = RegexRep::Range {
+                                min: Some(n),
+                                max: None,
+                            };
+                        }
+                        RegexRep::Range { min, max } => {
+                            if let Some(mut min_value) = min {
+                                min_value += 1;
                                 last.rep = RegexRep::Range {
-                                    min: Some(n),
-                                    max: None,
+                                    min: Some(min_value),
+                                    max,
                                 };
+                            } else {
+                                last.rep = RegexRep::Range { min: Some(1), max };
                             }
-                            RegexRep::Range { min, max } => {
-                                if let Some(mut min_value) = min {
-                                    min_value += 1;
-                                    last.rep = RegexRep::Range {
-                                        min: Some(min_value),
-                                        max,
-                                    };
-                                } else {
-                                    last.rep = RegexRep::Range { min: Some(1), max };
-                                }
-                            }
-                            _ => {}
                         }
-                    } else {
-                        return Err(GrepError::Err);
+                        _ => {}
                     }
                     None
                 }
-                '?' => {
-                    if let Some(last) = steps.last_mut() {
-                        println!("Encontrado '?', modificando el último paso: {:?}", last);
-                        last.rep = RegexRep::Range {
-                            min: Some(0),
-                            max: Some(1),
-                        };
-                    } else {
-                        return Err(GrepError::Err);
-                    }
+                (_, Some('?')) => {
+                    Regex::last_repeatable(&mut frame.steps)?.rep = RegexRep::Range {
+                        min: Some(0),
+                        max: Some(1),
+                    };
                     None
                 }
-                '{' => {
-                    println!("Procesando  con BracketExpression::read_bracket_expression_c");
-                    BracketExpression::read_bracket_expression_c(&mut chars_iter, &mut steps)?;
+                (_, Some(other)) => Some(RegexStep {
+                    rep: RegexRep::Exact(1),
+                    val: Regex::literal(other, flags),
+                }),
+                ('*', None) if dialect == Dialect::Basic && starts => Some(RegexStep {
+                    rep: RegexRep::Exact(1),
+                    val: Regex::literal('*', flags),
+                }),
+                ('*', None) => {
+                    Regex::last_repeatable(&mut frame.steps)?.rep = RegexRep::Any;
                     None
                 }
-                '[' => {
-                    println!("Procesando '['");
+                ('^', None) if dialect == Dialect::Extended || starts => {
+                    at_start = true;
+                    Some(RegexStep {
+                        rep: RegexRep::Exact(1),
+                        val: RegexValue::Inicio {
+                            multi_line: flags.multi_line,
+                        },
+                    })
+                }
+                ('$', None) if dialect == Dialect::Extended || Regex::ends_basic(&chars_iter) => {
+                    Some(RegexStep {
+                        rep: RegexRep::Exact(1),
+                        val: RegexValue::Fin {
+                            multi_line: flags.multi_line,
+                        },
+                    })
+                }
+                ('.', None) => Some(RegexStep {
+                    rep: RegexRep::Exact(1),
+                    val: Regex::wildcard(flags),
+                }),
+                ('[', None) => {
                     if chars_iter.clone().next() == Some('[') {
                         let class_content = CharacterClass::read_character_class(&mut chars_iter)?;
                         Some(RegexStep {
                             rep: RegexRep::Exact(1),
-                            val: class_content,
+                            val: Regex::clase(class_content, flags),
                         })
                     } else {
                         let bracket_content =
                             BracketExpression::read_bracket_expression(&mut chars_iter)?;
                         Some(RegexStep {
                             rep: RegexRep::Exact(1),
-                            val: bracket_content,
+                            val: Regex::clase(bracket_content, flags),
                         })
                     }
                 }
-                '\\' => {
-                    if let Some(special_char) = chars_iter.next() {
-                        Some(RegexStep {
-                            rep: RegexRep::Exact(1),
-                            val: RegexValue::Literal(special_char),
-                        })
+                ('\\', None) => {
+                    let val = if chars_iter.clone().next() == Some('s') {
+                        chars_iter.next();
+                        RegexValue::Clase(CharacterClass::Space)
                     } else {
-                        return Err(GrepError::Err);
-                    }
+                        Regex::literal(escape::read_escape(&mut chars_iter)?, flags)
+                    };
+                    Some(RegexStep {
+                        rep: RegexRep::Exact(1),
+                        val,
+                    })
                 }
+                (c, None) if c != '\n' => Some(RegexStep {
+                    rep: RegexRep::Exact(1),
+                    val: Regex::literal(c, flags),
+                }),
                 _ => return Err(GrepError::Err),
             };
 
+            prefix = false;
             if let Some(p) = step {
-                steps.push(p);
+                frames.last_mut().ok_or(GrepError::Err)?.steps.push(p);
             }
         }
 
-        println!("Creación de Regex completada con steps: {:?} y anchoring: {:?}", steps, anchoring);
-        Ok(Regex { steps, anchoring })
+        if frames.len() != 1 {
+            return Err(GrepError::Err);
+        }
+        frames.pop().map(GroupFrame::finish).ok_or(GrepError::Err)
     }
 
-    pub fn test(&self, value: &str) -> Result<bool, GrepError> {
-        if !value.is_ascii() {
-            return Err(GrepError::Err);
+    /// Devuelve el operador que representa `c` en el dialecto, o `None` si no es un operador.
+    /// Si `c` es una `\` que forma un operador con el caracter que sigue, también lo consume.
+    fn read_operator(c: char, chars_iter: &mut Chars, dialect: Dialect) -> Option<char> {
+        if c != '\\' {
+            return dialect.is_operator(c, false).then_some(c);
         }
-    
-        let mut index = 0;
-        let  queue = VecDeque::from(self.steps.clone());
-    
-        if self.anchoring.get_anchoring_end() {
-            if self.anchoring.matches_anchoring(&self.steps, value) {
-                return Ok(true);
-            } else {
-                return Ok(false);
+        let next = chars_iter.clone().next()?;
+        if dialect.is_operator(next, true) {
+            chars_iter.next();
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    /// Lee las letras de un grupo de modificadores, justo después del `(?`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve las letras y si el grupo termina en `:`, con alcance sólo dentro de sus
+    /// paréntesis, o en `)`.
+    ///
+    /// Si el patrón termina antes, devuelve un error de tipo `GrepError`.
+    fn read_flag_letters(
+        chars_iter: &mut Chars,
+        dialect: Dialect,
+    ) -> Result<(String, bool), GrepError> {
+        let mut letters = String::new();
+        while let Some(c) = chars_iter.next() {
+            if c == ':' {
+                return Ok((letters, true));
+            }
+            if Regex::read_operator(c, chars_iter, dialect) == Some(')') {
+                return Ok((letters, false));
+            }
+            letters.push(c);
+        }
+        Err(GrepError::Err)
+    }
+
+    /// Lee las repeticiones de un intervalo, justo después del `{` o del `\{`, y se las
+    /// asigna al último paso.
+    fn read_interval(
+        chars_iter: &mut Chars,
+        dialect: Dialect,
+        steps: &mut [RegexStep],
+    ) -> Result<(), GrepError> {
+        Regex::last_repeatable(steps)?;
+        if dialect == Dialect::Extended {
+            return BracketExpression::read_bracket_expression_c(chars_iter, steps);
+        }
+
+        let mut interval = String::new();
+        loop {
+            match chars_iter.next() {
+                Some('\\') if chars_iter.clone().next() == Some('}') => {
+                    chars_iter.next();
+                    interval.push('}');
+                    break;
+                }
+                Some('}') | None => return Err(GrepError::Err),
+                Some(c) => interval.push(c),
             }
         }
-        if self.anchoring.get_anchoring_start(){
-            if self.anchoring.matches_anchoring(&self.steps, value) {
-                return Ok(true);
+        BracketExpression::read_bracket_expression_c(&mut interval.chars(), steps)
+    }
+
+    /// Devuelve el último paso leído para cambiarle las repeticiones.
+    ///
+    /// Si no hay ninguno, o es un anclaje, devuelve un error de tipo `GrepError`.
+    fn last_repeatable(steps: &mut [RegexStep]) -> Result<&mut RegexStep, GrepError> {
+        match steps.last_mut() {
+            Some(RegexStep {
+                val: RegexValue::Inicio { .. } | RegexValue::Fin { .. },
+                ..
+            })
+            | None => Err(GrepError::Err),
+            Some(last) => Ok(last),
+        }
+    }
+
+    /// Indica si un `$` de la sintaxis básica es un anclaje: si está al final del patrón, de
+    /// un grupo o de una alternativa.
+    fn ends_basic(rest: &Chars) -> bool {
+        let rest = rest.as_str();
+        rest.is_empty() || rest.starts_with("\\)") || rest.starts_with("\\|")
+    }
+
+    /// Arma una expresión regular con los pasos de una alternativa.
+    ///
+    /// Simplifica los grupos, y el `^` del comienzo y el `$` del final pasan al anclaje, así
+    /// se prueban antes de recorrer los pasos.
+    fn from_steps(steps: Vec<RegexStep>) -> Regex {
+        let mut steps = Regex::simplify(steps);
+        let mut anchoring = Anchoring::new();
+        let mut multi_line_start = false;
+        let mut multi_line_end = false;
+
+        if let Some(RegexStep {
+            val: RegexValue::Inicio { multi_line },
+            ..
+        }) = steps.first()
+        {
+            multi_line_start = *multi_line;
+            anchoring.update_anchoring('^');
+            steps.remove(0);
+        }
+        if let Some(RegexStep {
+            val: RegexValue::Fin { multi_line },
+            ..
+        }) = steps.last()
+        {
+            multi_line_end = *multi_line;
+            anchoring.update_anchoring('$');
+            steps.pop();
+        }
+
+        Regex {
+            steps,
+            anchoring,
+            multi_line_start,
+            multi_line_end,
+        }
+    }
+
+    /// Reemplaza cada grupo de una sola alternativa que no se repite por sus pasos, y cada
+    /// grupo de un solo paso por ese paso con las repeticiones del grupo, como en `(x)*`.
+    fn simplify(steps: Vec<RegexStep>) -> Vec<RegexStep> {
+        let mut simplified = Vec::with_capacity(steps.len());
+        for step in steps {
+            let alternatives = match step.val {
+                RegexValue::Grupo(alternatives) => alternatives,
+                val => {
+                    simplified.push(RegexStep { val, rep: step.rep });
+                    continue;
+                }
+            };
+            let alternatives: Vec<Vec<RegexStep>> =
+                alternatives.into_iter().map(Regex::simplify).collect();
+
+            if alternatives.len() == 1 && step.rep == RegexRep::Exact(1) {
+                simplified.extend(alternatives.into_iter().flatten());
+                continue;
+            }
+            let single_step = match alternatives.as_slice() {
+                [only] => matches!(
+                    only.as_slice(),
+                    [inner] if inner.rep == RegexRep::Exact(1)
+                        && !matches!(inner.val, RegexValue::Inicio { .. } | RegexValue::Fin { .. })
+                ),
+                _ => false,
+            };
+            if single_step {
+                simplified.extend(alternatives.into_iter().flatten().map(|inner| RegexStep {
+                    val: inner.val,
+                    rep: step.rep.clone(),
+                }));
             } else {
-                return Ok(false);
+                simplified.push(RegexStep {
+                    val: RegexValue::Grupo(alternatives),
+                    rep: step.rep,
+                });
             }
         }
-    
-        while index < value.len() {
-            let mut local_index = index;
-            let mut local_queue = queue.clone();
-            let mut local_stack = Vec::new();
-    
-            'steps: while let Some(step) = local_queue.pop_front() {
-                match step.rep {
-                    RegexRep::Exact(n) => {
-                        let mut match_size = 0;
-                        for _ in 0..n {
-                            let size = if local_index == 0 && !self.anchoring.get_anchoring_start() {
-                                step.val.matches(&value[local_index..])
-                            } else {
-                                step.val.is_same(&value[local_index..])
-                            };
-                            if size == 0 {
-                                match EvaluatedStep::backtrack(step.clone(), &mut local_stack, &mut local_queue) {
-                                    Some(size) => {
-                                        local_index -= size;
-                                        continue 'steps;
-                                    }
-                                    None => break 'steps,
-                                }
-                            } else {
-                                match_size += size;
-                                local_index += size;
-                            }
-                        }
-                        local_stack.push(EvaluatedStep {
-                            step: step.clone(),
-                            match_size,
-                            backtrackeable: false,
-                        });
-                    }
-                    RegexRep::Any => {
-                        let mut keep_matching = true;
-                        while keep_matching {
-                            let match_size = if local_index == 0 && !self.anchoring.get_anchoring_start() {
-                                step.val.matches(&value[local_index..])
-                            } else {
-                                step.val.is_same(&value[local_index..])
-                            };
-                            if match_size != 0 {
-                                local_index += match_size;
-                                local_stack.push(EvaluatedStep {
-                                    step: step.clone(),
-                                    match_size,
-                                    backtrackeable: true,
-                                });
-                            } else {
-                                keep_matching = false;
-                            }
-                        }
-                    }
-                    RegexRep::Range { min, max } => {
-                        let mut count = 0;
-                        let mut match_size = 0;
-                        while count < match max {
-                            Some(value) => value,
-                            None => usize::MAX,
-                        } {
-                            let size = if local_index == 0 && !self.anchoring.get_anchoring_start() {
-                                step.val.matches(&value[local_index..])
-                            } else {
-                                step.val.is_same(&value[local_index..])
-                            };
-                            if size == 0 {
-                                break;
-                            }
-                            local_index += size;
-                            count += 1;
-                            match_size += size;
-                        }
-                        if let Some(min_value) = min {
-                            if count < min_value {
-                                break 'steps;
-                            }
-                        }
-                        local_stack.push(EvaluatedStep {
-                            step: step.clone(),
-                            match_size,
-                            backtrackeable: true,
-                        });
-                    }
+        simplified
+    }
+
+    /// Devuelve el valor de un literal, que con `(?i)` acepta también la otra mayúscula o
+    /// minúscula.
+    fn literal(c: char, flags: RegexFlags) -> RegexValue {
+        let variants = CharacterClass::case_variants(c);
+        if flags.case_insensitive && variants.len() > 1 {
+            RegexValue::Clase(CharacterClass::Custom(variants, false))
+        } else {
+            RegexValue::Literal(c)
+        }
+    }
+
+    /// Devuelve el valor de `.`, que sin `(?s)` acepta cualquier caracter menos `\n`.
+    fn wildcard(flags: RegexFlags) -> RegexValue {
+        if flags.dot_all {
+            RegexValue::Wildcard
+        } else {
+            RegexValue::Clase(CharacterClass::Custom(vec!['\n'], true))
+        }
+    }
+
+    /// Devuelve el valor de una clase, que con `(?i)` acepta también la otra mayúscula o
+    /// minúscula de sus caracteres.
+    fn clase(value: RegexValue, flags: RegexFlags) -> RegexValue {
+        match value {
+            RegexValue::Clase(clase) if flags.case_insensitive => {
+                RegexValue::Clase(clase.ignoring_case())
+            }
+            value => value,
+        }
+    }
+
+    /// Indica si la expresión regular coincide en alguna parte del texto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - El texto en el que se busca.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `true` si hay alguna coincidencia.
+    pub fn test(&self, value: &str) -> Result<bool, GrepError> {
+        Ok(self.find_at(value, 0).is_some())
+    }
+
+    /// Indica si la expresión regular coincide en alguna parte de unos bytes, que no
+    /// necesitan ser UTF-8 válido.
+    ///
+    /// Donde los bytes son UTF-8 válido, `.` y las clases consumen un caracter decodificado;
+    /// donde no, consumen un solo byte, que se compara como el caracter Latin-1 del mismo valor.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Los bytes en los que se busca.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `true` si hay alguna coincidencia.
+    pub fn is_match_bytes(&self, value: &[u8]) -> bool {
+        self.find_at_bytes(value, 0).is_some()
+    }
+
+    /// Busca la primera coincidencia que empieza en `start` o después.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - El texto en el que se busca.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin, en bytes, de la coincidencia que empieza más a la izquierda.
+    pub fn find_at(&self, value: &str, start: usize) -> Option<(usize, usize)> {
+        self.find_at_bytes(value.as_bytes(), start)
+    }
+
+    /// Igual que `find_at`, pero sobre bytes que no necesitan ser UTF-8 válido.
+    pub fn find_at_bytes(&self, value: &[u8], start: usize) -> Option<(usize, usize)> {
+        self.find_where_bytes(value, start, &|_, _| true)
+    }
+
+    /// Busca la primera coincidencia que empieza en `start` o después y que `accept` acepta.
+    ///
+    /// Si una coincidencia no es aceptada, se prueban las más cortas que empiezan en la misma
+    /// posición antes de pasar a la siguiente.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - El texto en el que se busca.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    /// * `accept` - Recibe el inicio y el fin de una coincidencia e indica si se acepta.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin, en bytes, de la coincidencia aceptada que empieza más a la izquierda.
+    pub fn find_where(
+        &self,
+        value: &str,
+        start: usize,
+        accept: &dyn Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        self.find_where_bytes(value.as_bytes(), start, accept)
+    }
+
+    /// Igual que `find_where`, pero sobre bytes que no necesitan ser UTF-8 válido.
+    pub fn find_where_bytes(
+        &self,
+        value: &[u8],
+        start: usize,
+        accept: &dyn Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        let mut begin = start;
+
+        while begin <= value.len() {
+            if !self.can_start_at(value, begin) {
+                if !self.multi_line_start {
+                    break;
                 }
+                begin += utf8::decode(&value[begin..]).map_or(1, |(_, size)| size);
+                continue;
             }
-    
-            if local_queue.is_empty() {
-                return Ok(true);
+
+            let mut found = None;
+            let matched = self.match_steps(&self.steps, value, begin, &mut |end| {
+                if !self.can_end_at(value, end) {
+                    return false;
+                }
+                if !accept(begin, end) {
+                    return false;
+                }
+                found = Some(end);
+                true
+            });
+
+            if matched {
+                return found.map(|end| (begin, end));
             }
-    
-            index += 1;
+            begin += utf8::decode(&value[begin..]).map_or(1, |(_, size)| size);
         }
-    
-        Ok(false)
+
+        None
     }
-    
 
-    pub fn crear_regex(regular_expression: &str) -> Result<Vec<Regex>, GrepError> {
-        let mut regex_vec: Vec<Regex> = Vec::new();
+    /// Devuelve los pasos de la expresión regular, en orden.
+    pub fn steps(&self) -> &[RegexStep] {
+        &self.steps
+    }
+
+    /// Indica si la expresión regular empieza con `^`.
+    pub fn is_anchored_start(&self) -> bool {
+        self.anchoring.get_anchoring_start()
+    }
+
+    /// Indica si una coincidencia puede empezar en `pos` según el anclaje `^`: al comienzo de
+    /// los bytes o, con `(?m)`, después de un `\n`.
+    pub fn can_start_at(&self, value: &[u8], pos: usize) -> bool {
+        !self.anchoring.get_anchoring_start()
+            || Regex::is_line_start(value, pos, self.multi_line_start)
+    }
+
+    /// Indica si una coincidencia puede terminar en `pos` según el anclaje `$`: al final de los
+    /// bytes o, con `(?m)`, antes de un `\n`.
+    pub fn can_end_at(&self, value: &[u8], pos: usize) -> bool {
+        !self.anchoring.get_anchoring_end() || Regex::is_line_end(value, pos, self.multi_line_end)
+    }
 
-        for subexpression in regular_expression.split('|') {
-            if !subexpression.is_empty() {
-                println!("Creando Regex para subexpresión: {}", subexpression);
-                let regex = Regex::new(subexpression)?;
-                regex_vec.push(regex);
+    /// Indica si `pos` es el comienzo de los bytes o, con `(?m)`, está después de un `\n`.
+    pub fn is_line_start(value: &[u8], pos: usize, multi_line: bool) -> bool {
+        pos == 0 || (multi_line && value[pos - 1] == b'\n')
+    }
+
+    /// Indica si `pos` es el final de los bytes o, con `(?m)`, está antes de un `\n`.
+    pub fn is_line_end(value: &[u8], pos: usize, multi_line: bool) -> bool {
+        pos == value.len() || (multi_line && value[pos] == b'\n')
+    }
+
+    /// Intenta hacer coincidir los pasos desde `pos`, probando primero las repeticiones más largas.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Los pasos que faltan evaluar.
+    /// * `value` - Los bytes en los que se busca.
+    /// * `pos` - La posición, en bytes, en la que empieza el primer paso.
+    /// * `next` - Recibe la posición final cuando coincidieron todos los pasos; si devuelve
+    ///   `false` se sigue retrocediendo.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `true` si coincidieron todos los pasos y `next` aceptó el final.
+    fn match_steps(
+        &self,
+        steps: &[RegexStep],
+        value: &[u8],
+        pos: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let (step, rest) = match steps.split_first() {
+            Some(split) => split,
+            None => return next(pos),
+        };
+        let (min, max) = step.rep.bounds();
+
+        match &step.val {
+            RegexValue::Grupo(alternatives) => {
+                return self.match_group(alternatives, min, max, value, pos, &mut |end| {
+                    self.match_steps(rest, value, end, next)
+                });
+            }
+            RegexValue::Inicio { multi_line } => {
+                return Regex::is_line_start(value, pos, *multi_line)
+                    && self.match_steps(rest, value, pos, next);
             }
+            RegexValue::Fin { multi_line } => {
+                return Regex::is_line_end(value, pos, *multi_line)
+                    && self.match_steps(rest, value, pos, next);
+            }
+            _ => {}
         }
 
-        println!("Regex creados: {:?}", regex_vec);
-        Ok(regex_vec)
+        let mut ends = Vec::new();
+        let mut end = pos;
+        while ends.len() < max {
+            let size = step.val.is_same_bytes(&value[end..]);
+            if size == 0 {
+                break;
+            }
+            end += size;
+            ends.push(end);
+        }
+
+        loop {
+            if ends.len() < min {
+                return false;
+            }
+            let end = ends.last().copied().unwrap_or(pos);
+            if self.match_steps(rest, value, end, next) {
+                return true;
+            }
+            if ends.pop().is_none() {
+                return false;
+            }
+        }
+    }
+
+    /// Intenta hacer coincidir un grupo que se repite entre `min` y `max` veces desde `pos`,
+    /// probando primero las repeticiones más largas y, en cada una, las alternativas en orden.
+    ///
+    /// Una repetición que no consume nada sólo cuenta para llegar al mínimo, así un grupo que
+    /// puede coincidir vacío, como en `(a*)*`, no se repite para siempre.
+    fn match_group(
+        &self,
+        alternatives: &[Vec<RegexStep>],
+        min: usize,
+        max: usize,
+        value: &[u8],
+        pos: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        if max > 0 {
+            let max_rest = if max == usize::MAX { max } else { max - 1 };
+            for alternative in alternatives {
+                let matched = self.match_steps(alternative, value, pos, &mut |end| {
+                    (end > pos || min > 0)
+                        && self.match_group(
+                            alternatives,
+                            min.saturating_sub(1),
+                            max_rest,
+                            value,
+                            end,
+                            next,
+                        )
+                });
+                if matched {
+                    return true;
+                }
+            }
+        }
+        min == 0 && next(pos)
+    }
+
+    pub fn crear_regex(regular_expression: &str) -> Result<Vec<Regex>, GrepError> {
+        Regex::crear_regex_con_flags(regular_expression, RegexFlags::default())
+    }
+
+    /// Compila cada alternativa de un patrón, empezando con los modificadores dados.
+    ///
+    /// El patrón se divide en los `|` que no están dentro de un grupo; las alternativas de un
+    /// grupo, como en `x(?i:a|b)y`, quedan en un paso de la misma expresión. Los grupos de
+    /// modificadores del comienzo, como `(?i)`, valen para todas las alternativas, y las
+    /// alternativas vacías se descartan. Un patrón vacío coincide con cualquier texto.
+    ///
+    /// # Arguments
+    ///
+    /// * `regular_expression` - El patrón completo.
+    /// * `flags` - Los modificadores con los que empieza cada alternativa, como los de `-i`.
+    ///
+    /// # Returns
+    ///
+    /// Si alguna alternativa no es válida, devuelve un error de tipo `GrepError`.
+    pub fn crear_regex_con_flags(
+        regular_expression: &str,
+        flags: RegexFlags,
+    ) -> Result<Vec<Regex>, GrepError> {
+        Regex::crear_regex_con_dialecto(regular_expression, flags, Dialect::Extended)
+    }
+
+    /// Compila cada alternativa de un patrón escrito en el dialecto dado, como
+    /// `crear_regex_con_flags`.
+    ///
+    /// Los patrones básicos de `-G` y los extendidos de `-E` se leen con el mismo parser, así
+    /// los dos dialectos producen los mismos pasos.
+    ///
+    /// # Arguments
+    ///
+    /// * `regular_expression` - El patrón completo.
+    /// * `flags` - Los modificadores con los que empieza cada alternativa, como los de `-i`.
+    /// * `dialect` - La sintaxis en la que está escrito el patrón.
+    ///
+    /// # Returns
+    ///
+    /// Si alguna alternativa no es válida, devuelve un error de tipo `GrepError`.
+    pub fn crear_regex_con_dialecto(
+        regular_expression: &str,
+        flags: RegexFlags,
+        dialect: Dialect,
+    ) -> Result<Vec<Regex>, GrepError> {
+        let alternatives = Regex::parse(regular_expression, flags, dialect)?;
+        let several = alternatives.len() > 1;
+        Ok(alternatives
+            .into_iter()
+            .filter(|steps| !several || !steps.is_empty())
+            .map(Regex::from_steps)
+            .collect())
     }
 }
 
@@ -569,122 +1052,111 @@ mod tests {
         assert_eq!(matches, false);
         Ok(())
     }
-   
-    #[test]
-fn test_catedra_uno() -> Result<(), GrepError> {
-    let value_0 = "abcd";
-    let value = "abcdd";
-    let value_1 = "abccd";
-    let value_2 = "hola abcd chau";
-    let regex = Regex::new("ab.d")?;
-
-    let matches = regex.clone().test(value)?;
-    let matches_0 = regex.clone().test(value_0)?;
-    let matches_1 = regex.clone().test(value_1)?;
-    let matches_2 = regex.clone().test(value_2)?;
-
-    assert_eq!(matches, true);
-    assert_eq!(matches_0, true);
-    assert_eq!(matches_1, false);
-    assert_eq!(matches_2, true);
-
-    Ok(())
-}
-#[test]
-fn test_catedra_dos() -> Result<(), GrepError> {
-    let value_0 = "absalolngopsgdehejsd";
-    let value = "abcdd";
-    let value_1 = "abd";
-    let value_2 = "que tul abuelita dime tu";
-    let value_3 = "hola abcd chau";
-    let value_4 = "te vamos a bochar";
-
-    let regex = Regex::new("ab.*d")?;
-
-    let matches = regex.clone().test(value)?;
-    let matches_0 = regex.clone().test(value_0)?;
-    let matches_1 = regex.clone().test(value_1)?;
-    let matches_2 = regex.clone().test(value_2)?;
-    let matches_3 = regex.clone().test(value_3)?;
-    let matches_4 = regex.clone().test(value_4)?;
-
-
-
-    assert_eq!(matches, true);
-    assert_eq!(matches_0, true);
-    assert_eq!(matches_1, true);
-    assert_eq!(matches_2, true);
-    assert_eq!(matches_3, true);
-    assert_eq!(matches_4, false);
 
+    #[test]
+    fn test_catedra_uno() -> Result<(), GrepError> {
+        let value_0 = "abcd";
+        let value = "abcdd";
+        let value_1 = "abccd";
+        let value_2 = "hola abcd chau";
+        let regex = Regex::new("ab.d")?;
 
+        let matches = regex.clone().test(value)?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches_1 = regex.clone().test(value_1)?;
+        let matches_2 = regex.clone().test(value_2)?;
 
-    Ok(())
-}
-#[test]
-fn test_catedra_tres() -> Result<(), GrepError> {
-    let value_0 = "abcd";
-    let value = "abcccd";
-    let value_1 = "hola abcccd chau";
+        assert_eq!(matches, true);
+        assert_eq!(matches_0, true);
+        assert_eq!(matches_1, false);
+        assert_eq!(matches_2, true);
 
+        Ok(())
+    }
+    #[test]
+    fn test_catedra_dos() -> Result<(), GrepError> {
+        let value_0 = "absalolngopsgdehejsd";
+        let value = "abcdd";
+        let value_1 = "abd";
+        let value_2 = "que tul abuelita dime tu";
+        let value_3 = "hola abcd chau";
+        let value_4 = "te vamos a bochar";
 
-    let regex = Regex::new("abc{3}d")?;
+        let regex = Regex::new("ab.*d")?;
 
-    let matches = regex.clone().test(value)?;
-    let matches_0 = regex.clone().test(value_0)?;
-    let matches_1 = regex.clone().test(value_1)?;
+        let matches = regex.clone().test(value)?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches_1 = regex.clone().test(value_1)?;
+        let matches_2 = regex.clone().test(value_2)?;
+        let matches_3 = regex.clone().test(value_3)?;
+        let matches_4 = regex.clone().test(value_4)?;
 
-    assert_eq!(matches_0, false);
-    assert_eq!(matches, true);
-    assert_eq!(matches_1, true);
+        assert_eq!(matches, true);
+        assert_eq!(matches_0, true);
+        assert_eq!(matches_1, true);
+        assert_eq!(matches_2, true);
+        assert_eq!(matches_3, true);
+        assert_eq!(matches_4, false);
 
+        Ok(())
+    }
+    #[test]
+    fn test_catedra_tres() -> Result<(), GrepError> {
+        let value_0 = "abcd";
+        let value = "abcccd";
+        let value_1 = "hola abcccd chau";
 
+        let regex = Regex::new("abc{3}d")?;
 
+        let matches = regex.clone().test(value)?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches_1 = regex.clone().test(value_1)?;
 
-    Ok(())
-}
-#[test]
-fn test_catedra_cuatro() -> Result<(), GrepError> {
-    let value_0 = "abcd abcd";
-    let value = "abd abcccd abd";
-    let value_1 = "abcccccccd abcd";
-    let value_2 = "en medio abccd abd fin";
+        assert_eq!(matches_0, false);
+        assert_eq!(matches, true);
+        assert_eq!(matches_1, true);
 
+        Ok(())
+    }
+    #[test]
+    fn test_catedra_cuatro() -> Result<(), GrepError> {
+        let value_0 = "abcd abcd";
+        let value = "abd abcccd abd";
+        let value_1 = "abcccccccd abcd";
+        let value_2 = "en medio abccd abd fin";
 
-    let regex = Regex::new("abc{2,5}d abc{0,}d")?;
+        let regex = Regex::new("abc{2,5}d abc{0,}d")?;
 
-    let matches = regex.clone().test(value)?;
-    let matches_0 = regex.clone().test(value_0)?;
-    let matches_1 = regex.clone().test(value_1)?;
-    let matches_2 = regex.clone().test(value_2)?;
+        let matches = regex.clone().test(value)?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches_1 = regex.clone().test(value_1)?;
+        let matches_2 = regex.clone().test(value_2)?;
 
-    assert_eq!(matches, true);
-    assert_eq!(matches_0, false);
-    assert_eq!(matches_1, false);
-    assert_eq!(matches_2, true);
+        assert_eq!(matches, true);
+        assert_eq!(matches_0, false);
+        assert_eq!(matches_1, false);
+        assert_eq!(matches_2, true);
 
+        Ok(())
+    }
+    #[test]
+    fn test_catedra_cinco() -> Result<(), GrepError> {
+        let value_0 = "abd";
+        let value = "abc";
+        let value_1 = "agd";
+        let value_2 = "cami figura abd";
 
+        let regex = Regex::new("a[bc]d")?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches = regex.clone().test(value)?;
+        let matches_1 = regex.clone().test(value_1)?;
+        let matches_2 = regex.clone().test(value_2)?;
 
-    Ok(())
-}
-#[test]
-fn test_catedra_cinco() -> Result<(), GrepError> {
-    let value_0 = "abd";
-    let value = "abc";
-    let value_1 = "agd";
-    let value_2 = "cami figura abd";
-    
-    let regex = Regex::new("a[bc]d")?;
-    let matches_0 = regex.clone().test(value_0)?;
-    let matches = regex.clone().test(value)?;
-    let matches_1 = regex.clone().test(value_1)?;
-    let matches_2 = regex.clone().test(value_2)?;
-
-    assert_eq!(matches_0, true);
-    assert_eq!(matches, false);
-    assert_eq!(matches_1, false);
-    assert_eq!(matches_2, true);
-    Ok(())
+        assert_eq!(matches_0, true);
+        assert_eq!(matches, false);
+        assert_eq!(matches_1, false);
+        assert_eq!(matches_2, true);
+        Ok(())
     }
     #[test]
     fn test_catedra_seis() -> Result<(), GrepError> {
@@ -693,90 +1165,352 @@ fn test_catedra_cinco() -> Result<(), GrepError> {
         let value_1 = "abcccd";
         let value_2 = "hola abcd chau";
 
-        
         let regex = Regex::new("abc+d")?;
         let matches_0 = regex.clone().test(value_0)?;
         let matches = regex.clone().test(value)?;
         let matches_1 = regex.clone().test(value_1)?;
         let matches_2 = regex.clone().test(value_2)?;
-    
+
         assert_eq!(matches_0, true);
         assert_eq!(matches, false);
         assert_eq!(matches_1, true);
         assert_eq!(matches_2, true);
         Ok(())
+    }
+    #[test]
+    fn test_catedra_siete() -> Result<(), GrepError> {
+        let value_0 = "abcd";
+        let value = "abcdd";
+        let value_1 = "abd";
+        let value_2 = "hola abcd chau";
+        let value_3 = "abhhd";
+        let regex = Regex::new("ab.?d")?;
+
+        let matches = regex.clone().test(value)?;
+        let matches_0 = regex.clone().test(value_0)?;
+        let matches_1 = regex.clone().test(value_1)?;
+        let matches_2 = regex.clone().test(value_2)?;
+        let matches_3 = regex.clone().test(value_3)?;
+
+        assert_eq!(matches, true);
+        assert_eq!(matches_0, true);
+        assert_eq!(matches_1, true);
+        assert_eq!(matches_2, true);
+        assert_eq!(matches_3, false);
+
+        Ok(())
+    }
+    #[test]
+    fn test_apple_or_melon() -> Result<(), GrepError> {
+        let input = "banana\napple\norange\npineapple\nsoy melon\nen el medio watermelon va";
+        let regexes = Regex::crear_regex("apple|melon")?;
+        let mut expected_output = String::new();
+
+        for regex in regexes {
+            let mut matched_lines = String::new();
+            for line in input.lines() {
+                if regex.test(line)? {
+                    matched_lines.push_str(line);
+                    matched_lines.push('\n');
+                }
+            }
+            expected_output.push_str(&matched_lines);
         }
-        #[test]
-        fn test_catedra_siete() -> Result<(), GrepError> {
-            let value_0 = "abcd";
-            let value = "abcdd";
-            let value_1 = "abd";
-            let value_2 = "hola abcd chau";
-            let value_3 = "abhhd";
-            let regex = Regex::new("ab.?d")?;
-    
-            let matches = regex.clone().test(value)?;
-            let matches_0 = regex.clone().test(value_0)?;
-            let matches_1 = regex.clone().test(value_1)?;
-            let matches_2 = regex.clone().test(value_2)?;
-            let matches_3 = regex.clone().test(value_3)?;
-    
-            assert_eq!(matches, true);
-            assert_eq!(matches_0, true);
-            assert_eq!(matches_1, true);
-            assert_eq!(matches_2, true);
-            assert_eq!(matches_3, false);
-    
-            Ok(())
-        }
-        #[test]
-fn test_apple_or_melon() -> Result<(), GrepError> {
-    let input = "banana\napple\norange\npineapple\nsoy melon\nen el medio watermelon va";
-    let regexes = Regex::crear_regex("apple|melon")?;
-    let mut expected_output = String::new();
-
-    for regex in regexes {
-        let mut matched_lines = String::new();
-        for line in input.lines() {
-            if regex.test(line)? {
-                matched_lines.push_str(line);
-                matched_lines.push('\n');
+
+        let output = "apple\npineapple\nsoy melon\nen el medio watermelon va\n";
+
+        assert_eq!(expected_output, output);
+
+        Ok(())
+    }
+    #[test]
+    fn test_complex_regex() -> Result<(), GrepError> {
+        let input = "abc?def\n123*456\n789+10\nhola?\nesta no tiene que estar\nesta tampoco";
+        let regexes = Regex::crear_regex("abc\\?def|123\\*456|789\\+10")?;
+        let mut expected_output = String::new();
+
+        for regex in regexes {
+            let mut matched_lines = String::new();
+            for line in input.lines() {
+                if regex.test(line)? {
+                    matched_lines.push_str(line);
+                    matched_lines.push('\n');
+                }
             }
+            expected_output.push_str(&matched_lines);
+        }
+
+        let output = "abc?def\n123*456\n789+10\n";
+
+        assert_eq!(expected_output, output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flags() -> Result<(), GrepError> {
+        let text = "fn foo(\n    bar)\nbaz\n";
+        assert_eq!(
+            Regex::new("foo\\(\\n\\s*bar")?.find_at(text, 0),
+            Some((3, 15))
+        );
+        assert!(!Regex::new("foo.*bar")?.test(text)?);
+        assert!(Regex::new("(?s)foo.*bar")?.test(text)?);
+        assert!(!Regex::new("^baz")?.test(text)?);
+        assert_eq!(Regex::new("(?m)^baz$")?.find_at(text, 0), Some((17, 20)));
+        assert!(Regex::new("(?m)bar.$")?.test(text)?);
+
+        let regexes = Regex::crear_regex("(?s)a.b|c.d")?;
+        assert!(regexes
+            .iter()
+            .all(|regex| regex.test("a\nb c\nd").unwrap_or(false)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_inline_flags() -> Result<(), GrepError> {
+        assert!(Regex::new("(?i)hola mundo")?.test("HOLA Mundo")?);
+        assert!(Regex::new("(?i:ab)c")?.test("ABc")?);
+        assert!(!Regex::new("(?i:ab)c")?.test("ABC")?);
+        assert!(Regex::new("a(?i)b")?.test("aB")?);
+        assert!(!Regex::new("a(?i)b")?.test("AB")?);
+        assert!(Regex::new("(?i)[[:lower:]]+ [xñ]")?.test("ABC Ñ")?);
+        assert!(!Regex::new("(?i)[^a]")?.test("aA")?);
+        assert!(Regex::new("(?i)x(?-i:y)")?.test("Xy")?);
+        assert!(!Regex::new("(?i)x(?-i:y)")?.test("XY")?);
+        assert!(Regex::new("(?m:b$)")?.test("b\nc")?);
+        assert!(!Regex::new("(?s:a).b")?.test("a\nb")?);
+        assert!(Regex::new("(?m:^a)b$")?.test("x\nab")?);
+        assert!(!Regex::new("(?m:^a)b$")?.test("ab\nc")?);
+
+        let regex = Regex::new("(?x) a b+ # comentario\n c \\ d")?;
+        assert!(regex.test("abbc d")?);
+        assert!(!regex.test("a b c d")?);
+
+        assert!(Regex::new("(?i:ab").is_err());
+        assert!(Regex::new("ab)").is_err());
+        assert!(Regex::new("(ab").is_err());
+        assert!(Regex::new("(?z)ab").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_crear_regex_empty() -> Result<(), GrepError> {
+        for pattern in ["", "(?i)"] {
+            let regexes = Regex::crear_regex(pattern)?;
+            assert_eq!(regexes.len(), 1);
+            assert_eq!(regexes[0].find_at("kiwi", 0), Some((0, 0)));
+            assert!(regexes[0].test("")?);
         }
-        expected_output.push_str(&matched_lines);
+        Ok(())
     }
 
-    let output = "apple\npineapple\nsoy melon\nen el medio watermelon va\n";
+    #[test]
+    fn test_crear_regex_con_flags() -> Result<(), GrepError> {
+        let regexes = Regex::crear_regex("(?i)ab|(?-i:d)e|[|]")?;
+        assert_eq!(regexes.len(), 3);
+        assert!(regexes[0].test("AB")?);
+        assert!(regexes[1].test("dE")?);
+        assert!(!regexes[1].test("DE")?);
+        assert!(regexes[2].test("|")?);
+
+        let flags = RegexFlags {
+            case_insensitive: true,
+            ..RegexFlags::default()
+        };
+        let regexes = Regex::crear_regex_con_flags("ab|(?-i)cd", flags)?;
+        assert!(regexes[0].test("aB")?);
+        assert!(!regexes[1].test("CD")?);
+        Ok(())
+    }
 
-    assert_eq!(expected_output, output);
+    #[test]
+    fn test_scoped_alternatives() -> Result<(), GrepError> {
+        let regexes = Regex::crear_regex("x(?i:a|b)y")?;
+        assert_eq!(regexes.len(), 1);
+        assert!(regexes[0].test("xAy")?);
+        assert!(regexes[0].test("xBy")?);
+        assert!(!regexes[0].test("XaY")?);
+
+        let regexes = Regex::crear_regex("^(?i:a(?s:b|.)|c)$|d")?;
+        assert_eq!(regexes.len(), 2);
+        assert!(regexes[0].test("A\n")?);
+        assert!(regexes[0].test("C")?);
+        assert!(!regexes[0].test("xc")?);
+        assert!(!regexes[0].test("a\nb")?);
+
+        assert!(Regex::crear_regex("(?i:a[|]b)")?[0].test("A|B")?);
+        assert!(Regex::crear_regex("(?i:x)*y")?[0].test("XXy")?);
+        assert_eq!(Regex::new("^(?i:a|b)*$")?.find_at("aBba", 0), Some((0, 4)));
+        assert_eq!(Regex::new("(?i:ab)+")?.find_at("xaBAbab", 0), Some((1, 7)));
+        assert_eq!(Regex::new("(?i:)?")?.find_at("kiwi", 0), Some((0, 0)));
+        assert!(Regex::new("x(a|b)y")?.test("xby")?);
+        assert!(matches!(Regex::new("a|b"), Err(GrepError::ErrRegex(_))));
+        assert!(matches!(Regex::crear_regex("(?i:a|b"), Err(GrepError::Err)));
+        assert_eq!(
+            GrepError::ErrRegex(
+                "las alternativas con `|` se compilan con `crear_regex`".to_string()
+            )
+            .to_string(),
+            "Expresión regular inválida: las alternativas con `|` se compilan con `crear_regex`"
+        );
+        Ok(())
+    }
 
-    Ok(())
-}
-#[test]
-fn test_complex_regex() -> Result<(), GrepError> {
-    let input = "abc?def\n123*456\n789+10\nhola?\nesta no tiene que estar\nesta tampoco";
-    let regexes = Regex::crear_regex("abc\\?def|123\\*456|789\\+10")?;
-    let mut expected_output = String::new();
-
-    for regex in regexes {
-        let mut matched_lines = String::new();
-        for line in input.lines() {
-            if regex.test(line)? {
-                matched_lines.push_str(line);
-                matched_lines.push('\n');
+    #[test]
+    fn test_literals_and_escapes() -> Result<(), GrepError> {
+        assert!(Regex::new("a,b-c:d/e=ñ}")?.test("xa,b-c:d/e=ñ}")?);
+        assert!(Regex::new("col1\\tcol2")?.test("col1\tcol2")?);
+        assert!(Regex::new("\\r\\f\\v\\0$")?.test("x\r\u{0c}\u{0b}\0")?);
+        assert!(Regex::new("caf\\xe9 \\x{263A} \\u{1F34C}")?.test("café ☺ \u{1f34c}")?);
+        assert!(Regex::new("[\\t,;]")?.test("a;b")?);
+        assert!(Regex::new("^[^\\x20\\]]+$")?.test("abc")?);
+        assert!(!Regex::new("^[^\\x20\\]]+$")?.test("a]c")?);
+        assert!(Regex::new("\\.\\*")?.test("a.*")?);
+
+        assert!(Regex::new("a\tb")?.test("xa\tb")?);
+        assert!(Regex::new("a\nb").is_err());
+        assert!(Regex::new("\\x4").is_err());
+        assert!(Regex::new("[\\u{D800}]").is_err());
+
+        let regexes = Regex::crear_regex("[\\]|]x|y")?;
+        assert_eq!(regexes.len(), 2);
+        assert!(regexes[0].test("|x")?);
+        Ok(())
+    }
+
+    /// Casos que se comprueban en los dos dialectos: el patrón básico, el extendido
+    /// equivalente, un texto y si coincide.
+    const CONFORMANCE: &[(&str, &str, &str, bool)] = &[
+        ("ab\\+c", "ab+c", "xabbbc", true),
+        ("ab\\+c", "ab+c", "ac", false),
+        ("colou\\?r", "colou?r", "color", true),
+        ("a\\{2,3\\}", "a{2,3}", "xaay", true),
+        ("a\\{2\\}b", "a{2}b", "ab", false),
+        ("^ban\\|melon$", "^ban|melon$", "watermelon", true),
+        ("^ban\\|melon$", "^ban|melon$", "a banana", false),
+        ("f(x)", "f\\(x\\)", "y = f(x)", true),
+        ("1+1=2?", "1\\+1=2\\?", "1+1=2?", true),
+        ("1+1=2?", "1\\+1=2\\?", "11=2", false),
+        ("{1}", "\\{1\\}", "a{1}", true),
+        ("a|b", "a\\|b", "a|b", true),
+        ("a|b", "a\\|b", "a", false),
+        ("*ab", "\\*ab", "x*ab", true),
+        ("a^b$c", "a\\^b\\$c", "a^b$c", true),
+        ("\\(?i\\)kiwi", "(?i)kiwi", "KIWI", true),
+        ("\\(?i:k\\)iwi", "(?i:k)iwi", "KIWI", false),
+        ("a\\(b\\)c", "a(b)c", "xabcx", true),
+        ("\\(ab\\|cd\\)e", "(ab|cd)e", "cde", true),
+        ("\\(ab\\|cd\\)e", "(ab|cd)e", "ade", false),
+        ("\\(^a\\)b\\(c$\\)", "(^a)b(c$)", "abc", true),
+        ("x\\(y\\)*z", "x(y)*z", "xyyz", true),
+        ("\\(ab\\)*c", "(ab)*c", "xababc", true),
+        ("^\\(ab\\)*$", "^(ab)*$", "aba", false),
+        ("\\(ab\\)\\{2\\}", "(ab){2}", "abab", true),
+        ("\\(ab\\)\\{2\\}", "(ab){2}", "abxab", false),
+        ("\\(a\\|b\\)\\+c", "(a|b)+c", "xbabc", true),
+        ("^\\(a\\|bc\\)*$", "^(a|bc)*$", "abca", true),
+        ("^\\(a\\|bc\\)*$", "^(a|bc)*$", "acb", false),
+        ("\\(^a\\|b\\)c", "(^a|b)c", "xac", false),
+        ("\\(*a\\|*b\\)", "(\\*a|\\*b)", "x*b", true),
+        ("\\(?i:a\\|b\\)*c", "(?i:a|b)*c", "ABac", true),
+        ("[(|+]x", "[(|+]x", "+x", true),
+        ("[[:digit:]]\\+$", "[[:digit:]]+$", "abc 123", true),
+        ("a.c\\t", "a.c\\t", "abc\t", true),
+    ];
+
+    #[test]
+    fn test_dialect_conformance() -> Result<(), GrepError> {
+        for &(basic, extended, text, matches) in CONFORMANCE {
+            let flags = RegexFlags::default();
+            let from_basic = Regex::crear_regex_con_dialecto(basic, flags, Dialect::Basic)?;
+            let from_extended =
+                Regex::crear_regex_con_dialecto(extended, flags, Dialect::Extended)?;
+            assert_eq!(
+                format!("{:?}", from_basic),
+                format!("{:?}", from_extended),
+                "{}",
+                basic
+            );
+            for regexes in [from_basic, from_extended] {
+                let found = regexes
+                    .iter()
+                    .any(|regex| regex.is_match_bytes(text.as_bytes()));
+                assert_eq!(found, matches, "{} en {:?}", extended, text);
             }
         }
-        expected_output.push_str(&matched_lines);
+        Ok(())
     }
 
-    let output = "abc?def\n123*456\n789+10\n";
+    #[test]
+    fn test_dialect_errors() {
+        let flags = RegexFlags::default();
+        assert!(Regex::crear_regex_con_dialecto("a\\{x\\}", flags, Dialect::Basic).is_err());
+        assert!(Regex::crear_regex_con_dialecto("a{x}", flags, Dialect::Basic).is_ok());
+        assert!(Regex::crear_regex_con_dialecto("a{x}", flags, Dialect::Extended).is_err());
+    }
 
-    assert_eq!(expected_output, output);
+    #[test]
+    fn test_group_repetitions() -> Result<(), GrepError> {
+        assert_eq!(Regex::new("(ab)*c")?.find_at("xababc", 0), Some((1, 6)));
+        assert_eq!(Regex::new("(ab){2}")?.find_at("ab abab", 0), Some((3, 7)));
+        assert_eq!(Regex::new("(a|bc)+d")?.find_at("xabcad", 0), Some((1, 6)));
+        assert_eq!(
+            Regex::new("(a|ab)(c|bcd)")?.find_at("abcd", 0),
+            Some((0, 4))
+        );
+        assert_eq!(Regex::new("^(a*)*$")?.find_at("aaa", 0), Some((0, 3)));
+        assert!(!Regex::new("^(a*)*$")?.test("aab")?);
+        assert!(Regex::new("^(ab|a)*b$")?.test("aabab")?);
+        assert!(Regex::new("(x(y|z)?){2,3}$")?.test("xzxxy")?);
+        assert!(!Regex::new("^(xy){2,}$")?.test("xyx")?);
+
+        assert!(Regex::new("(^a|b)c")?.test("bc")?);
+        assert!(!Regex::new("(^a|b)c")?.test("xac")?);
+        assert!(Regex::new("a(b$|c)")?.test("xacx")?);
+        assert!(!Regex::new("a(b$|c)")?.test("abx")?);
+        assert!(Regex::new("(?m)x\\n(^a|b)")?.test("x\na")?);
+        assert!(!Regex::new("x\\n(^a|b)")?.test("x\na")?);
+        Ok(())
+    }
 
-    Ok(())
-}
+    #[test]
+    fn test_is_match_bytes() -> Result<(), GrepError> {
+        assert!(Regex::new("caf.")?.is_match_bytes(b"un caf\xe9 con leche"));
+        assert!(Regex::new("^c[[:alpha:]]f")?.is_match_bytes(b"caf\xe1"));
+        assert!(Regex::new("a.b$")?.is_match_bytes(b"xa\xffb"));
+        assert!(!Regex::new("a.b")?.is_match_bytes(b"a\xff\xfeb"));
+        assert_eq!(
+            Regex::new("b+")?.find_at_bytes(b"\xffabbc", 0),
+            Some((2, 4))
+        );
+        Ok(())
+    }
 
+    #[test]
+    fn test_find_at() -> Result<(), GrepError> {
+        let regex = Regex::new("ab.*d")?;
+        assert_eq!(regex.find_at("xx abcd abd", 0), Some((3, 11)));
+        assert_eq!(regex.find_at("xx abcd abd", 4), Some((8, 11)));
+        assert_eq!(regex.find_at("xx abc", 0), None);
+
+        let regex = Regex::new("^ab")?;
+        assert_eq!(regex.find_at("abab", 0), Some((0, 2)));
+        assert_eq!(regex.find_at("abab", 1), None);
+        Ok(())
+    }
 
+    #[test]
+    fn test_find_where_backtracks() -> Result<(), GrepError> {
+        let regex = Regex::new("a.*")?;
+        assert_eq!(
+            regex.find_where("abcabc", 0, &|_, end| end == 3),
+            Some((0, 3))
+        );
+        assert_eq!(
+            regex.find_where("abcabc", 0, &|start, _| start > 0),
+            Some((3, 6))
+        );
+        Ok(())
+    }
 }
-
-
@@ -0,0 +1,115 @@
+use crate::grep_error::GrepError;
+
+/// Representa los modificadores de una expresión regular, activados con `(?i)`, `(?m)`, `(?s)`
+/// y `(?x)` para el resto del patrón, o con `(?i:...)` sólo dentro del grupo.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RegexFlags {
+    /// `(?i)`: no distingue mayúsculas de minúsculas.
+    pub case_insensitive: bool,
+    /// `(?m)`: `^` y `$` coinciden también al comienzo y al final de cada línea.
+    pub multi_line: bool,
+    /// `(?s)`: `.` coincide también con `\n`.
+    pub dot_all: bool,
+    /// `(?x)`: ignora los espacios y los comentarios que empiezan con `#`.
+    pub extended: bool,
+}
+
+impl RegexFlags {
+    /// Devuelve los modificadores que resultan de aplicar las letras de un grupo, como `is` o
+    /// `i-m`; las letras después de `-` desactivan el modificador.
+    ///
+    /// # Arguments
+    ///
+    /// * `letters` - Las letras entre `(?` y `)` o `:`.
+    ///
+    /// # Returns
+    ///
+    /// Si hay una letra desconocida, devuelve un error de tipo `GrepError`.
+    pub fn apply(&self, letters: &str) -> Result<RegexFlags, GrepError> {
+        let mut flags = *self;
+        let mut enable = true;
+        for letter in letters.chars() {
+            match letter {
+                '-' if enable => enable = false,
+                'i' => flags.case_insensitive = enable,
+                'm' => flags.multi_line = enable,
+                's' => flags.dot_all = enable,
+                'x' => flags.extended = enable,
+                _ => return Err(GrepError::Err),
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Separa los grupos de modificadores del comienzo de un patrón, como en `(?s)(?m)abc`, y
+    /// los aplica a estos modificadores.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - El patrón completo.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve los modificadores y el resto del patrón. Un grupo con alcance, como `(?i:abc)`,
+    /// queda en el resto.
+    ///
+    /// Si un grupo tiene un modificador desconocido, devuelve un error de tipo `GrepError`.
+    pub fn read_prefix<'e>(&self, expression: &'e str) -> Result<(RegexFlags, &'e str), GrepError> {
+        let mut flags = *self;
+        let mut rest = expression;
+        while let Some(group) = rest.strip_prefix("(?") {
+            let letters = match group.split_once(')') {
+                Some((letters, _)) if !letters.contains(':') => letters,
+                _ => break,
+            };
+            flags = flags.apply(letters)?;
+            rest = &group[letters.len() + 1..];
+        }
+        Ok((flags, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() -> Result<(), GrepError> {
+        let flags = RegexFlags::default().apply("isx")?;
+        assert!(flags.case_insensitive && flags.dot_all && flags.extended);
+        assert!(!flags.multi_line);
+
+        let flags = flags.apply("m-ix")?;
+        assert!(flags.multi_line && flags.dot_all);
+        assert!(!flags.case_insensitive && !flags.extended);
+
+        assert!(RegexFlags::default().apply("q").is_err());
+        assert!(RegexFlags::default().apply("i--m").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_prefix() -> Result<(), GrepError> {
+        let (flags, rest) = RegexFlags::default().read_prefix("abc")?;
+        assert_eq!(flags, RegexFlags::default());
+        assert_eq!(rest, "abc");
+
+        let (flags, rest) = RegexFlags::default().read_prefix("(?sm)^a.b")?;
+        assert!(flags.multi_line && flags.dot_all);
+        assert_eq!(rest, "^a.b");
+
+        let (flags, rest) = RegexFlags::default().read_prefix("(?s)(?i)")?;
+        assert!(flags.dot_all && flags.case_insensitive);
+        assert_eq!(rest, "");
+
+        let (flags, rest) = RegexFlags::default().read_prefix("(?i:ab)c")?;
+        assert_eq!(flags, RegexFlags::default());
+        assert_eq!(rest, "(?i:ab)c");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_prefix_errors() {
+        assert!(RegexFlags::default().read_prefix("(?q)abc").is_err());
+    }
+}
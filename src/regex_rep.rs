@@ -8,3 +8,14 @@ pub enum RegexRep {
         max: Option<usize>,
     },
 }
+
+impl RegexRep {
+    /// Devuelve la cantidad mínima y máxima de repeticiones permitidas.
+    pub fn bounds(&self) -> (usize, usize) {
+        match self {
+            RegexRep::Any => (0, usize::MAX),
+            RegexRep::Exact(n) => (*n, *n),
+            RegexRep::Range { min, max } => (min.unwrap_or(0), max.unwrap_or(usize::MAX)),
+        }
+    }
+}
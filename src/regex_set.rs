@@ -0,0 +1,531 @@
+use std::{collections::HashMap, mem};
+
+use crate::{
+    character_class::CharacterClass, grep_error::GrepError, regex::Regex, regex_step::RegexStep,
+    regex_value::RegexValue, utf8,
+};
+
+/// Reúne muchos patrones en un solo autómata que indica, en una pasada por la línea, cuáles
+/// coinciden.
+///
+/// Los pasos de todas las expresiones regulares se despliegan en los nodos de un autómata no
+/// determinista, que se simula de izquierda a derecha avanzando a la vez todos los estados
+/// vivos. Sirve para el `-e` y el `-f` de la línea de comandos, y para quien necesite saber
+/// qué reglas de una lista coinciden con un texto.
+#[derive(Debug, Clone)]
+pub struct RegexSet {
+    /// Las alternativas de todos los patrones.
+    regexes: Vec<Regex>,
+    /// El índice del patrón al que pertenece cada alternativa.
+    owners: Vec<usize>,
+    /// El primer nodo de cada alternativa.
+    starts: Vec<usize>,
+    nodes: Vec<Node>,
+    patterns: usize,
+    /// Las alternativas que empiezan con `^`, o con un anclaje dentro de un grupo, que se
+    /// prueban en cada posición en la que pueden empezar.
+    anchored: Vec<usize>,
+    /// Los nodos de entrada de las demás alternativas que consumen un caracter conocido,
+    /// indexados por ese caracter.
+    entry_by_char: HashMap<char, Vec<usize>>,
+    /// Los nodos de entrada que consumen un caracter de una clase que no se puede indexar.
+    entry_other: Vec<usize>,
+    /// Los nodos de entrada que ya aceptan, como los de los patrones vacíos.
+    entry_accepts: Vec<usize>,
+}
+
+/// Un estado del autómata.
+#[derive(Debug, Clone)]
+enum Node {
+    /// Consume un caracter que coincide con el valor y pasa al nodo `next`.
+    Char { val: RegexValue, next: usize },
+    /// Pasa, sin consumir nada, a todos estos nodos a la vez.
+    Split(Vec<usize>),
+    /// Pasa al nodo `next` si se cumple el anclaje `^` o `$` del valor.
+    Assert { val: RegexValue, next: usize },
+    /// La alternativa de este índice coincidió entera.
+    Accept(usize),
+}
+
+impl RegexSet {
+    /// Compila los patrones, en la sintaxis extendida y sin modificadores.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Los patrones; cada uno puede tener alternativas separadas por `|`.
+    ///
+    /// # Returns
+    ///
+    /// Si algún patrón no es válido, devuelve un error de tipo `GrepError`.
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<RegexSet, GrepError> {
+        let groups = patterns
+            .iter()
+            .map(|pattern| Regex::crear_regex(pattern.as_ref()))
+            .collect::<Result<Vec<Vec<Regex>>, GrepError>>()?;
+        Ok(RegexSet::from_regexes(groups))
+    }
+
+    /// Arma el autómata a partir de patrones ya compilados, como los de `Regex::crear_regex`.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` - Las alternativas de cada patrón; el índice de cada grupo es el índice que
+    ///   devuelve `matches`.
+    pub fn from_regexes(groups: Vec<Vec<Regex>>) -> RegexSet {
+        let mut set = RegexSet {
+            regexes: Vec::new(),
+            owners: Vec::new(),
+            starts: Vec::new(),
+            nodes: Vec::new(),
+            patterns: groups.len(),
+            anchored: Vec::new(),
+            entry_by_char: HashMap::new(),
+            entry_other: Vec::new(),
+            entry_accepts: Vec::new(),
+        };
+        for (owner, group) in groups.into_iter().enumerate() {
+            for regex in group {
+                set.push(owner, regex);
+            }
+        }
+        set.index_entries();
+        set
+    }
+
+    /// Devuelve la cantidad de patrones.
+    pub fn len(&self) -> usize {
+        self.patterns
+    }
+
+    /// Indica si no hay ningún patrón.
+    pub fn is_empty(&self) -> bool {
+        self.patterns == 0
+    }
+
+    /// Indica si algún patrón coincide en alguna parte de la línea.
+    ///
+    /// La línea puede ser texto o bytes que no son UTF-8 válido. Deja de recorrerla apenas
+    /// coincide uno.
+    pub fn is_match<T: AsRef<[u8]> + ?Sized>(&self, line: &T) -> bool {
+        self.matched_regexes(line.as_ref(), true)
+            .iter()
+            .any(|&matched| matched)
+    }
+
+    /// Devuelve los índices, en orden, de los patrones que coinciden en alguna parte de la línea.
+    ///
+    /// La línea puede ser texto o bytes que no son UTF-8 válido, y se recorre una sola vez sin
+    /// importar cuántos patrones haya.
+    pub fn matches<T: AsRef<[u8]> + ?Sized>(&self, line: &T) -> Vec<usize> {
+        let mut matched = vec![false; self.patterns];
+        for (regex, found) in self
+            .matched_regexes(line.as_ref(), false)
+            .iter()
+            .enumerate()
+        {
+            if *found {
+                matched[self.owners[regex]] = true;
+            }
+        }
+        (0..self.patterns).filter(|&index| matched[index]).collect()
+    }
+
+    /// Busca la primera coincidencia aceptada que empieza en `start` o después, entre todos los
+    /// patrones.
+    ///
+    /// Si hay varias alternativas, el autómata descarta primero las que no coinciden en ninguna
+    /// parte desde `start`, y sólo las demás se buscan con `Regex::find_where_bytes` para
+    /// ubicar la coincidencia.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Los bytes en los que se busca, que no necesitan ser UTF-8 válido.
+    /// * `start` - La posición, en bytes, desde la que se empieza a buscar.
+    /// * `accept` - Recibe el inicio y el fin de una coincidencia e indica si se acepta.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin de la coincidencia que empieza más a la izquierda; si
+    /// empiezan en el mismo lugar, la más larga.
+    pub fn find_where_bytes(
+        &self,
+        value: &[u8],
+        start: usize,
+        accept: &dyn Fn(usize, usize) -> bool,
+    ) -> Option<(usize, usize)> {
+        let candidates = match (self.regexes.len(), value.get(start..)) {
+            (0 | 1, _) | (_, None) => vec![true; self.regexes.len()],
+            (_, Some(rest)) => self.matched_regexes(rest, false),
+        };
+        let mut best: Option<(usize, usize)> = None;
+
+        for (regex, _) in self
+            .regexes
+            .iter()
+            .zip(candidates)
+            .filter(|(_, candidate)| *candidate)
+        {
+            if let Some((begin, end)) = regex.find_where_bytes(value, start, accept) {
+                best = match best {
+                    Some((b, e)) if b < begin || (b == begin && e >= end) => Some((b, e)),
+                    _ => Some((begin, end)),
+                };
+            }
+        }
+
+        best
+    }
+
+    /// Agrega los nodos de una alternativa, desde el final hacia el comienzo, así cada paso ya
+    /// conoce el nodo al que pasa cuando coincide.
+    fn push(&mut self, owner: usize, regex: Regex) {
+        let accept = self.push_node(Node::Accept(self.regexes.len()));
+        let start = self.push_steps(regex.steps(), accept);
+        self.starts.push(start);
+        self.owners.push(owner);
+        self.regexes.push(regex);
+    }
+
+    /// Agrega los nodos de unos pasos que terminan en `next`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el nodo en el que empiezan los pasos.
+    fn push_steps(&mut self, steps: &[RegexStep], next: usize) -> usize {
+        steps
+            .iter()
+            .rev()
+            .fold(next, |next, step| self.push_step(step, next))
+    }
+
+    /// Agrega los nodos de un paso que termina en `next`: el valor se repite tantas veces como
+    /// el mínimo, y después se agregan las repeticiones opcionales hasta el máximo, o un ciclo
+    /// si no tiene máximo.
+    fn push_step(&mut self, step: &RegexStep, next: usize) -> usize {
+        let (min, max) = step.rep.bounds();
+        let mut entry = next;
+        if max == usize::MAX {
+            let cycle = self.push_node(Node::Split(Vec::new()));
+            let body = self.push_value(&step.val, cycle);
+            self.nodes[cycle] = Node::Split(vec![body, next]);
+            entry = cycle;
+        } else {
+            for _ in min..max {
+                let body = self.push_value(&step.val, entry);
+                entry = self.push_node(Node::Split(vec![body, next]));
+            }
+        }
+        for _ in 0..min {
+            entry = self.push_value(&step.val, entry);
+        }
+        entry
+    }
+
+    /// Agrega los nodos de una sola repetición de un valor que termina en `next`.
+    fn push_value(&mut self, val: &RegexValue, next: usize) -> usize {
+        match val {
+            RegexValue::Grupo(alternatives) => {
+                let entries = alternatives
+                    .iter()
+                    .map(|steps| self.push_steps(steps, next))
+                    .collect();
+                self.push_node(Node::Split(entries))
+            }
+            RegexValue::Inicio { .. } | RegexValue::Fin { .. } => self.push_node(Node::Assert {
+                val: val.clone(),
+                next,
+            }),
+            _ => self.push_node(Node::Char {
+                val: val.clone(),
+                next,
+            }),
+        }
+    }
+
+    /// Agrega un nodo y devuelve su índice.
+    fn push_node(&mut self, node: Node) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Calcula los nodos en los que puede empezar, en cualquier posición, cada alternativa que
+    /// no empieza con un anclaje, y los indexa por el caracter que consumen para no probarlos
+    /// todos en cada posición.
+    fn index_entries(&mut self) {
+        let mut marks = vec![usize::MAX; self.nodes.len()];
+        let mut entries = Vec::new();
+        for (index, regex) in self.regexes.iter().enumerate() {
+            if regex.is_anchored_start() || self.reaches_assert(self.starts[index]) {
+                self.anchored.push(index);
+            } else {
+                self.add_state(self.starts[index], &[], 0, &mut marks, &mut entries);
+            }
+        }
+
+        for node in entries {
+            match &self.nodes[node] {
+                Node::Accept(_) => self.entry_accepts.push(node),
+                Node::Char { val, .. } => match RegexSet::first_chars(val) {
+                    Some(chars) => {
+                        for c in chars {
+                            self.entry_by_char.entry(c).or_default().push(node);
+                        }
+                    }
+                    None => self.entry_other.push(node),
+                },
+                Node::Split(_) | Node::Assert { .. } => {}
+            }
+        }
+    }
+
+    /// Indica si desde un nodo se llega a un anclaje sin consumir nada, y entonces sus estados
+    /// dependen de la posición.
+    fn reaches_assert(&self, start: usize) -> bool {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut pending = vec![start];
+        while let Some(node) = pending.pop() {
+            if mem::replace(&mut visited[node], true) {
+                continue;
+            }
+            match &self.nodes[node] {
+                Node::Assert { .. } => return true,
+                Node::Split(targets) => pending.extend(targets),
+                Node::Char { .. } | Node::Accept(_) => {}
+            }
+        }
+        false
+    }
+
+    /// Devuelve los únicos caracteres que acepta un valor, si son pocos y conocidos.
+    fn first_chars(val: &RegexValue) -> Option<Vec<char>> {
+        match val {
+            RegexValue::Literal(c) => Some(vec![*c]),
+            RegexValue::Clase(CharacterClass::Custom(chars, false)) => Some(chars.clone()),
+            _ => None,
+        }
+    }
+
+    /// Simula el autómata sobre la línea.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - Los bytes en los que se busca.
+    /// * `first_only` - Si se deja de buscar apenas coincide una alternativa.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve, para cada alternativa, si coincide en alguna parte de la línea.
+    fn matched_regexes(&self, line: &[u8], first_only: bool) -> Vec<bool> {
+        let mut matched = vec![false; self.regexes.len()];
+        let mut pending = self.regexes.len();
+        let mut marks = vec![usize::MAX; self.nodes.len()];
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            for &index in &self.anchored {
+                if !matched[index] && self.regexes[index].can_start_at(line, pos) {
+                    self.add_state(self.starts[index], line, pos, &mut marks, &mut current);
+                }
+            }
+            for &node in current.iter().chain(&self.entry_accepts) {
+                if let Node::Accept(index) = self.nodes[node] {
+                    if !matched[index] && self.regexes[index].can_end_at(line, pos) {
+                        matched[index] = true;
+                        pending -= 1;
+                        if first_only {
+                            return matched;
+                        }
+                    }
+                }
+            }
+            if pending == 0 {
+                break;
+            }
+
+            let (c, size) = match utf8::decode(&line[pos..]) {
+                Some(decoded) => decoded,
+                None => break,
+            };
+            let entries = self.entry_by_char.get(&c).into_iter().flatten();
+            for &node in current.iter().chain(&self.entry_other).chain(entries) {
+                if let Node::Char { val, next: target } = &self.nodes[node] {
+                    if val.is_same_bytes(&line[pos..]) > 0 {
+                        self.add_state(*target, line, pos + size, &mut marks, &mut next);
+                    }
+                }
+            }
+            current.clear();
+            mem::swap(&mut current, &mut next);
+            pos += size;
+        }
+
+        matched
+    }
+
+    /// Agrega un estado vivo en `pos`, junto con los siguientes a los que se llega sin consumir
+    /// nada. Sólo guarda los nodos que consumen un caracter o aceptan.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - El nodo al que se llegó.
+    /// * `line` - Los bytes en los que se busca, para comprobar los anclajes.
+    /// * `pos` - La posición, en bytes, a la que se llegó.
+    /// * `marks` - La última posición en la que se agregó cada nodo, para no repetirlo.
+    /// * `states` - Los estados vivos en `pos`.
+    fn add_state(
+        &self,
+        node: usize,
+        line: &[u8],
+        pos: usize,
+        marks: &mut [usize],
+        states: &mut Vec<usize>,
+    ) {
+        let mut pending = vec![node];
+        while let Some(node) = pending.pop() {
+            if marks[node] == pos {
+                continue;
+            }
+            marks[node] = pos;
+            match &self.nodes[node] {
+                Node::Char { .. } | Node::Accept(_) => states.push(node),
+                Node::Split(targets) => pending.extend(targets.iter().rev()),
+                Node::Assert { val, next } => {
+                    let holds = match val {
+                        RegexValue::Inicio { multi_line } => {
+                            Regex::is_line_start(line, pos, *multi_line)
+                        }
+                        RegexValue::Fin { multi_line } => {
+                            Regex::is_line_end(line, pos, *multi_line)
+                        }
+                        _ => true,
+                    };
+                    if holds {
+                        pending.push(*next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() -> Result<(), GrepError> {
+        let set = RegexSet::new(&["^ban", "mel.n$", "kiwi|apple", "x{2,3}"])?;
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.matches("banana"), vec![0]);
+        assert_eq!(set.matches("pineapple melon"), vec![1, 2]);
+        assert_eq!(set.matches("a banana"), Vec::<usize>::new());
+        assert_eq!(set.matches("axxb"), vec![3]);
+        assert_eq!(set.matches("axb"), Vec::<usize>::new());
+        assert_eq!(
+            RegexSet::new(&["^GET", "\\.png$"])?.matches("GET /logo.png"),
+            vec![0, 1]
+        );
+        assert!(set.is_match("un kiwi"));
+        assert!(!set.is_match("naranja"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_repetitions() -> Result<(), GrepError> {
+        let set = RegexSet::new(&["^ab*c$", "^a.?b+$", "^[[:digit:]]{2}-x$"])?;
+        assert_eq!(set.matches("ac"), vec![0]);
+        assert_eq!(set.matches("abbbc"), vec![0]);
+        assert_eq!(set.matches("abb"), vec![1]);
+        assert_eq!(set.matches("azb"), vec![1]);
+        assert_eq!(set.matches("azzb"), Vec::<usize>::new());
+        assert_eq!(set.matches("12-x"), vec![2]);
+        assert_eq!(set.matches("1-x"), Vec::<usize>::new());
+        Ok(())
+    }
+
+    #[test]
+    fn test_flags_and_bytes() -> Result<(), GrepError> {
+        let set = RegexSet::new(&["(?i)KIWI", "(?m)^b$", "caf."])?;
+        assert_eq!(set.matches("un kiwi"), vec![0]);
+        assert_eq!(set.matches("a\nb\nc"), vec![1]);
+        assert_eq!(set.matches(b"caf\xe9"), vec![2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> Result<(), GrepError> {
+        assert!(!RegexSet::new::<&str>(&[])?.is_match("abc"));
+        assert!(RegexSet::new::<&str>(&[])?.is_empty());
+        assert_eq!(RegexSet::new(&["", "z"])?.matches("abc"), vec![0]);
+        assert_eq!(RegexSet::new(&["z", "$"])?.matches("abc"), vec![1]);
+        assert_eq!(
+            RegexSet::new(&["(?m)b$", "x*"])?.matches("b\nc"),
+            vec![0, 1]
+        );
+        assert!(RegexSet::new(&["a", "("]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_agrees_with_regex() -> Result<(), GrepError> {
+        let patterns = [
+            "a.c",
+            "^ab+",
+            "b?c$",
+            "[[:alpha:]]{3}",
+            "(?i)B",
+            "z*",
+            "(ab|c)+$",
+            "(^a|z)b?",
+            "a(b(c|z)?)*",
+        ];
+        let lines = ["abc", "xabbc", "ac", "", "AB", "12 c", "zzz", "abcab", "zb"];
+        let set = RegexSet::new(&patterns)?;
+        for line in lines {
+            let mut expected = Vec::new();
+            for (index, pattern) in patterns.iter().enumerate() {
+                if Regex::new(pattern)?.test(line)? {
+                    expected.push(index);
+                }
+            }
+            assert_eq!(set.matches(line), expected, "{:?}", line);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_groups() -> Result<(), GrepError> {
+        let set = RegexSet::new(&["^(ab)+$", "x(a|bc){2}y", "(^k|w)i", "(a*)*z"])?;
+        assert_eq!(set.matches("abab"), vec![0]);
+        assert_eq!(set.matches("aba"), Vec::<usize>::new());
+        assert_eq!(set.matches("-xbcay-"), vec![1]);
+        assert_eq!(set.matches("xay"), Vec::<usize>::new());
+        assert_eq!(set.matches("kiwi"), vec![2]);
+        assert_eq!(set.matches("a ki"), Vec::<usize>::new());
+        assert_eq!(set.matches("aaz"), vec![3]);
+        assert_eq!(
+            RegexSet::new(&["(?m)a(b$|c)", "(?m)(x|^y)+"])?.matches("ab\nyy"),
+            vec![0, 1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_where_bytes() -> Result<(), GrepError> {
+        let set = RegexSet::new(&["melon", "apple|ap"])?;
+        assert_eq!(
+            set.find_where_bytes(b"apple melon", 0, &|_, _| true),
+            Some((0, 5))
+        );
+        assert_eq!(
+            set.find_where_bytes(b"apple melon", 1, &|_, _| true),
+            Some((6, 11))
+        );
+        let found = set.find_where_bytes(b"apple melon", 0, &|_, end| end != 5);
+        assert_eq!(found, Some((0, 2)));
+        assert_eq!(set.find_where_bytes(b"kiwi", 0, &|_, _| true), None);
+        Ok(())
+    }
+}
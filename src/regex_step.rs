@@ -1,6 +1,6 @@
 use crate::{regex_rep::RegexRep, regex_value::RegexValue};
 /// Representa un paso individual en una expresión regular.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RegexStep {
     pub val: RegexValue,
     pub rep: RegexRep,
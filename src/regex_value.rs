@@ -1,11 +1,24 @@
-use crate::character_class::CharacterClass;
+use crate::{character_class::CharacterClass, regex_step::RegexStep, utf8};
 
 /// Representa un valor en una expresión regular, que puede ser un carácter literal, un comodín o una clase de caracteres.
+///
+/// También puede ser un grupo, que coincide si coincide alguna de sus alternativas, o un
+/// anclaje `^` o `$` que no está al comienzo o al final del patrón, como en `(^a|b)c`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RegexValue {
     Literal(char),
     Wildcard,
     Clase(CharacterClass),
+    /// Los pasos de cada alternativa de un grupo `(...)`.
+    Grupo(Vec<Vec<RegexStep>>),
+    /// `^`: coincide al comienzo del texto o, con `(?m)`, después de un `\n`.
+    Inicio {
+        multi_line: bool,
+    },
+    /// `$`: coincide al final del texto o, con `(?m)`, antes de un `\n`.
+    Fin {
+        multi_line: bool,
+    },
 }
 
 impl RegexValue {
@@ -45,6 +58,7 @@ impl RegexValue {
                 }
                 0
             }
+            RegexValue::Grupo(_) | RegexValue::Inicio { .. } | RegexValue::Fin { .. } => 0,
         }
     }
 
@@ -60,32 +74,37 @@ impl RegexValue {
     ///
     /// Si no se encuentra ninguna coincidencia al inicio del texto, devuelve 0.
     pub fn is_same(&self, value: &str) -> usize {
-        match self {
-            RegexValue::Literal(c) => {
-                if value.starts_with(*c) {
-                    c.len_utf8()
-                } else {
-                    0
-                }
-            }
-            RegexValue::Wildcard => {
-                if let Some(next_char) = value.chars().next() {
-                    next_char.len_utf8()
-                } else {
-                    0
-                }
-            }
-            RegexValue::Clase(clase) => {
-                if let Some(c) = value.chars().next() {
-                    if clase.valid_character(c) {
-                        c.len_utf8()
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
+        self.is_same_bytes(value.as_bytes())
+    }
+
+    /// Devuelve la longitud de la coincidencia del valor al inicio de unos bytes.
+    ///
+    /// Donde los bytes son UTF-8 válido se compara el caracter decodificado; un byte inválido
+    /// se compara como el caracter Latin-1 del mismo valor, y `.` lo acepta como a cualquiera.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Los bytes en los que se va a buscar la coincidencia del valor.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la cantidad de bytes del caracter que coincide, o 0 si no coincide. Los grupos
+    /// y los anclajes no consumen un caracter por sí solos, y también devuelven 0.
+    pub fn is_same_bytes(&self, value: &[u8]) -> usize {
+        let (c, size) = match utf8::decode(value) {
+            Some(decoded) => decoded,
+            None => return 0,
+        };
+        let same = match self {
+            RegexValue::Literal(literal) => *literal == c,
+            RegexValue::Wildcard => true,
+            RegexValue::Clase(clase) => clase.valid_character(c),
+            RegexValue::Grupo(_) | RegexValue::Inicio { .. } | RegexValue::Fin { .. } => false,
+        };
+        if same {
+            size
+        } else {
+            0
         }
     }
 }
@@ -149,4 +168,18 @@ mod tests {
         assert_eq!(value_custom.is_same("abc"), 1);
         assert_eq!(value_custom.is_same("123"), 0);
     }
+
+    #[test]
+    fn test_is_same_bytes() {
+        assert_eq!(RegexValue::Wildcard.is_same_bytes(b"\xff"), 1);
+        assert_eq!(RegexValue::Literal('\u{e9}').is_same_bytes(b"\xe9t"), 1);
+        assert_eq!(
+            RegexValue::Literal('\u{e9}').is_same_bytes("\u{e9}t".as_bytes()),
+            2
+        );
+        let value = RegexValue::Clase(CharacterClass::Alpha);
+        assert_eq!(value.is_same_bytes(b"a\xff"), 1);
+        assert_eq!(value.is_same_bytes("ñ".as_bytes()), 0);
+        assert_eq!(value.is_same_bytes(b"\x01"), 0);
+    }
 }
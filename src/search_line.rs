@@ -0,0 +1,9 @@
+/// Representa una línea leída por el `Searcher`, junto con su posición en la entrada.
+///
+/// El texto son los bytes tal como se leyeron, sin el terminador; no necesitan ser UTF-8 válido.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchLine<'a> {
+    pub number: usize,
+    pub offset: usize,
+    pub text: &'a [u8],
+}
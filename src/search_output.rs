@@ -0,0 +1,12 @@
+use crate::grep_error::GrepError;
+
+/// Representa el resultado de buscar en una entrada desde un hilo de trabajo.
+#[derive(Debug)]
+pub struct SearchOutput {
+    /// La posición de la entrada en el orden del recorrido.
+    pub index: usize,
+    /// Lo que se imprimió al buscar en la entrada, ya formateado.
+    pub output: Vec<u8>,
+    /// Si se seleccionó alguna línea, o el nombre de la entrada y el error al buscar en ella.
+    pub result: Result<bool, (String, GrepError)>,
+}
@@ -0,0 +1,795 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{BufRead, Write},
+    mem, str,
+};
+
+use crate::{
+    binary_files::BinaryFiles, grep_error::GrepError, grep_options::GrepOptions, matcher::Matcher,
+    output_mode::OutputMode, printer::Printer, record_split::RecordSplit, search_line::SearchLine,
+    utf8,
+};
+
+/// Busca línea por línea en un lector, reutilizando un único buffer para no cargar la entrada entera en memoria.
+///
+/// Para el contexto anterior a una coincidencia guarda sólo las últimas líneas necesarias
+/// en un buffer circular. Con `--record-start` o `--record-separator` cada "línea" es un
+/// registro que puede ocupar varias líneas de la entrada.
+#[derive(Debug, Default, Clone)]
+pub struct Searcher {
+    buffer: Vec<u8>,
+    next_line: Vec<u8>,
+    unique: bool,
+    invert_match: bool,
+    output_mode: OutputMode,
+    max_count: Option<usize>,
+    before_context: usize,
+    after_context: usize,
+    before: VecDeque<(usize, usize, usize, Vec<u8>)>,
+    last_printed: Option<usize>,
+    binary_files: BinaryFiles,
+    terminator: u8,
+    records: RecordSplit,
+    multiline: bool,
+}
+
+impl Searcher {
+    /// Crea un `Searcher` con el buffer vacío, configurado según las opciones.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Las opciones leídas de la línea de comandos.
+    ///
+    /// # Returns
+    ///
+    /// Si el patrón de `--record-start` no es válido, devuelve un error de tipo `GrepError`.
+    pub fn new(options: &GrepOptions) -> Result<Searcher, GrepError> {
+        Ok(Searcher {
+            buffer: Vec::new(),
+            next_line: Vec::new(),
+            unique: options.unique,
+            invert_match: options.invert_match,
+            output_mode: options.output_mode,
+            max_count: options.max_count,
+            before_context: Searcher::context(options, options.before_context),
+            after_context: Searcher::context(options, options.after_context),
+            before: VecDeque::with_capacity(Searcher::context(options, options.before_context)),
+            last_printed: None,
+            binary_files: options.binary_files,
+            terminator: options.line_terminator(),
+            records: RecordSplit::new(options)?,
+            multiline: options.multiline,
+        })
+    }
+
+    /// Lee la entrada línea por línea e imprime cada línea seleccionada apenas se encuentra.
+    ///
+    /// Se seleccionan las líneas que coinciden, o las que no coinciden con `-v`. Cada línea
+    /// seleccionada se imprime una vez por aparición; con `--unique` se omiten las líneas
+    /// idénticas a una ya impresa de la misma entrada. Si se pidió contexto, también se
+    /// imprimen las líneas cercanas, separando con `--` los grupos que no son contiguos.
+    ///
+    /// Si el modo de salida no es `OutputMode::Lines` no se imprime nada, y con `-l`, `-L`
+    /// y `-q` se deja de leer apenas se selecciona la primera línea. Con `-m` se deja de leer
+    /// después de seleccionar esa cantidad de líneas y de imprimir su contexto posterior, sin
+    /// consumir ninguna línea de más.
+    ///
+    /// La entrada se considera binaria si hay un byte NUL en el primer bloque leído, salvo con
+    /// `-z`, o desde la primera línea que no es UTF-8 válido. En ese caso, salvo con `-a`, en
+    /// lugar de imprimir las líneas se imprime `Binary file X matches` en la primera
+    /// seleccionada y se deja de leer; con `-I` se deja de leer apenas se detecta, como si no
+    /// hubiera coincidencias.
+    ///
+    /// Con `-U` se lee la entrada entera y el patrón se busca en todo el texto, así que puede
+    /// coincidir con varias líneas; se seleccionan todas las líneas que toca alguna coincidencia.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - El lector sobre la entrada.
+    /// * `name` - El nombre de la entrada, para anteponerlo a las líneas si corresponde.
+    /// * `matcher` - El `Matcher` con el que se prueba cada línea.
+    /// * `printer` - El `Printer` donde se escriben las líneas seleccionadas.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve la cantidad de líneas seleccionadas.
+    ///
+    /// Si hay un error al leer la entrada o al escribir, devuelve un error de tipo `GrepError`.
+    pub fn search<R: BufRead, W: Write>(
+        &mut self,
+        reader: &mut R,
+        name: &str,
+        matcher: &Matcher,
+        printer: &mut Printer<W>,
+    ) -> Result<usize, GrepError> {
+        if !self.multiline {
+            return self.search_lines(reader, name, matcher, printer, None);
+        }
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|_| GrepError::ErrArchivo)?;
+        let touched = self.touched_lines(&data, matcher);
+        self.search_lines(&mut data.as_slice(), name, matcher, printer, Some(&touched))
+    }
+
+    /// Recorre la entrada línea por línea, o registro por registro, para `search`.
+    ///
+    /// # Arguments
+    ///
+    /// * `touched` - Con `-U`, las partes de la entrada, ordenadas, que ocupan las líneas que
+    ///   toca alguna coincidencia; una línea coincide si está dentro de alguna. Si no, cada
+    ///   línea se prueba con el `Matcher`.
+    fn search_lines<R: BufRead, W: Write>(
+        &mut self,
+        reader: &mut R,
+        name: &str,
+        matcher: &Matcher,
+        printer: &mut Printer<W>,
+        touched: Option<&[(usize, usize)]>,
+    ) -> Result<usize, GrepError> {
+        let mut buffer = mem::take(&mut self.buffer);
+        let printing = self.output_mode == OutputMode::Lines;
+        let mut count = 0;
+        let mut printed: HashSet<Vec<u8>> = HashSet::new();
+        let mut index = 0;
+        let mut number = 0;
+        let mut offset = 0;
+        let mut after_remaining = 0;
+        let mut region = 0;
+
+        let detects_binary = self.binary_files != BinaryFiles::Text;
+        let mut binary = detects_binary && self.starts_binary(reader)?;
+
+        self.before.clear();
+        self.next_line.clear();
+        self.last_printed = None;
+
+        while !self.reached_max(count) || (printing && after_remaining > 0) {
+            if binary && self.binary_files == BinaryFiles::WithoutMatch {
+                break;
+            }
+            if !self.read_record(reader, &mut buffer)? {
+                break;
+            }
+            let text = self.line_text(&buffer);
+            if detects_binary && !binary && str::from_utf8(text).is_err() {
+                binary = true;
+                if self.binary_files == BinaryFiles::WithoutMatch {
+                    break;
+                }
+            }
+            let line = SearchLine {
+                number: number + 1,
+                offset,
+                text,
+            };
+            let matches = match touched {
+                Some(regions) => Searcher::touches(regions, &mut region, offset, buffer.len()),
+                None => matcher.is_match(line.text),
+            };
+            index += 1;
+            number += self.lines_in(&buffer);
+            offset += buffer.len();
+
+            let selected = !self.reached_max(count)
+                && matches != self.invert_match
+                && (!self.unique || printed.insert(line.text.to_vec()));
+
+            if selected {
+                count += 1;
+                if !printing {
+                    if self.output_mode.stops_at_first() {
+                        break;
+                    }
+                    continue;
+                }
+                if binary {
+                    printer.print_binary_match(name)?;
+                    break;
+                }
+                self.print_before(name, matcher, printer)?;
+                self.print(name, index, &line, true, matcher, printer)?;
+                after_remaining = self.after_context;
+            } else if !printing {
+                continue;
+            } else if after_remaining > 0 {
+                self.print(name, index, &line, false, matcher, printer)?;
+                after_remaining -= 1;
+            } else if self.before_context > 0 {
+                self.remember(index, &line);
+            }
+        }
+
+        self.buffer = buffer;
+        Ok(count)
+    }
+
+    /// Busca todas las coincidencias en la entrada entera, para `-U`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Todo el contenido de la entrada.
+    /// * `matcher` - El `Matcher` con el que se busca.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve el inicio y el fin, en bytes, de las líneas completas que toca cada coincidencia,
+    /// ordenadas y uniendo las que se superponen.
+    fn touched_lines(&self, data: &[u8], matcher: &Matcher) -> Vec<(usize, usize)> {
+        let mut regions: Vec<(usize, usize)> = Vec::new();
+        let mut start = 0;
+        while let Some((begin, end)) = matcher.find_at(data, start) {
+            if begin == data.len() && data.last().is_none_or(|&last| last == self.terminator) {
+                break;
+            }
+            let line_start = data[..begin]
+                .iter()
+                .rposition(|&byte| byte == self.terminator)
+                .map_or(0, |i| i + 1);
+            let last = end.max(begin + 1) - 1;
+            let line_end = data[last.min(data.len())..]
+                .iter()
+                .position(|&byte| byte == self.terminator)
+                .map_or(data.len(), |i| last + i + 1);
+
+            match regions.last_mut() {
+                Some(previous) if previous.1 >= line_start => previous.1 = previous.1.max(line_end),
+                _ => regions.push((line_start, line_end)),
+            }
+            start = if end > begin {
+                end
+            } else {
+                begin + utf8::decode(&data[begin..]).map_or(1, |(_, size)| size)
+            };
+        }
+        regions
+    }
+
+    /// Indica si la línea que empieza en `offset` y ocupa `len` bytes está dentro de alguna de
+    /// las partes tocadas por una coincidencia.
+    ///
+    /// `region` es la primera parte que todavía puede contener líneas; como las líneas se
+    /// recorren en orden, avanza junto con ellas.
+    fn touches(regions: &[(usize, usize)], region: &mut usize, offset: usize, len: usize) -> bool {
+        while regions.get(*region).is_some_and(|&(_, end)| end <= offset) {
+            *region += 1;
+        }
+        regions
+            .get(*region)
+            .is_some_and(|&(start, _)| start < offset + len.max(1))
+    }
+
+    /// Devuelve la cantidad de líneas de contexto a imprimir; con `-o` no se imprime contexto.
+    fn context(options: &GrepOptions, lines: usize) -> usize {
+        if options.only_matching {
+            0
+        } else {
+            lines
+        }
+    }
+
+    /// Indica si ya se seleccionaron las líneas pedidas con `-m`.
+    fn reached_max(&self, count: usize) -> bool {
+        self.max_count.is_some_and(|max| count >= max)
+    }
+
+    /// Guarda una línea no impresa en el buffer circular del contexto anterior,
+    /// descartando la más vieja si ya está lleno.
+    fn remember(&mut self, index: usize, line: &SearchLine) {
+        let mut text = if self.before.len() == self.before_context {
+            self.before
+                .pop_front()
+                .map(|(_, _, _, text)| text)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        text.clear();
+        text.extend_from_slice(line.text);
+        self.before
+            .push_back((index, line.number, line.offset, text));
+    }
+
+    /// Imprime como contexto las líneas guardadas en el buffer circular y lo vacía.
+    fn print_before<W: Write>(
+        &mut self,
+        name: &str,
+        matcher: &Matcher,
+        printer: &mut Printer<W>,
+    ) -> Result<(), GrepError> {
+        let mut before = mem::take(&mut self.before);
+        for (index, number, offset, text) in before.drain(..) {
+            let line = SearchLine {
+                number,
+                offset,
+                text: &text,
+            };
+            self.print(name, index, &line, false, matcher, printer)?;
+        }
+        self.before = before;
+        Ok(())
+    }
+
+    /// Indica si se separan con `--` los grupos de líneas que no son contiguos, es decir, si se
+    /// pidió contexto.
+    pub fn separates_groups(&self) -> bool {
+        self.before_context > 0 || self.after_context > 0
+    }
+
+    /// Imprime una línea seleccionada o de contexto, anteponiendo `--` si no es contigua a la
+    /// última línea impresa. `index` es la posición de la línea, o del registro, en la entrada.
+    fn print<W: Write>(
+        &mut self,
+        name: &str,
+        index: usize,
+        line: &SearchLine,
+        selected: bool,
+        matcher: &Matcher,
+        printer: &mut Printer<W>,
+    ) -> Result<(), GrepError> {
+        let contiguous = match self.last_printed {
+            Some(last) => index == last + 1,
+            None => !printer.has_printed(),
+        };
+        if self.separates_groups() && !contiguous {
+            printer.print_group_separator()?;
+        }
+        self.last_printed = Some(index);
+
+        if selected {
+            printer.print_line(name, line, matcher)
+        } else {
+            printer.print_context(name, line, matcher)
+        }
+    }
+
+    /// Lee el siguiente registro en el buffer, reemplazando el anterior.
+    ///
+    /// Sin `--record-start` ni `--record-separator` cada registro es una línea. Con
+    /// `--record-start` se lee hasta la próxima línea que empieza un registro, que queda
+    /// guardada para la siguiente llamada.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `false` si se llegó al final de la entrada.
+    ///
+    /// Si hay un error al leer la entrada, devuelve un error de tipo `GrepError`.
+    fn read_record<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+        buffer: &mut Vec<u8>,
+    ) -> Result<bool, GrepError> {
+        buffer.clear();
+        match &self.records {
+            RecordSplit::Lines => self.read_line(reader, buffer),
+            RecordSplit::Separator(separator) => {
+                let last = separator[separator.len() - 1];
+                loop {
+                    let read = reader
+                        .read_until(last, buffer)
+                        .map_err(|_| GrepError::ErrArchivo)?;
+                    if read == 0 || buffer.ends_with(separator) {
+                        return Ok(!buffer.is_empty());
+                    }
+                }
+            }
+            RecordSplit::Start(start) => {
+                let mut next_line = mem::take(&mut self.next_line);
+                buffer.append(&mut next_line);
+                while self.read_line(reader, &mut next_line)? {
+                    if !buffer.is_empty() && start.is_match(self.line_text(&next_line)) {
+                        break;
+                    }
+                    buffer.append(&mut next_line);
+                }
+                self.next_line = next_line;
+                Ok(!buffer.is_empty())
+            }
+        }
+    }
+
+    /// Lee la siguiente línea en el buffer, reemplazando la anterior. Con `-z` las líneas
+    /// terminan en NUL en lugar de `\n`.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve `false` si se llegó al final de la entrada.
+    ///
+    /// Si hay un error al leer la entrada, devuelve un error de tipo `GrepError`.
+    fn read_line<R: BufRead>(
+        &self,
+        reader: &mut R,
+        buffer: &mut Vec<u8>,
+    ) -> Result<bool, GrepError> {
+        buffer.clear();
+        match reader.read_until(self.terminator, buffer) {
+            Ok(leidos) => Ok(leidos > 0),
+            Err(_) => Err(GrepError::ErrArchivo),
+        }
+    }
+
+    /// Devuelve cuántas líneas de la entrada ocupa un registro leído, para numerar el siguiente.
+    fn lines_in(&self, buffer: &[u8]) -> usize {
+        if !self.records.is_multiline() {
+            return 1;
+        }
+        let terminators = buffer
+            .iter()
+            .filter(|&&byte| byte == self.terminator)
+            .count();
+        match buffer.last() {
+            Some(&last) if last != self.terminator => terminators + 1,
+            _ => terminators,
+        }
+    }
+
+    /// Indica si el primer bloque de la entrada tiene algún byte NUL, sin consumirlo. Con `-z`
+    /// los NUL separan las líneas y no indican que la entrada sea binaria.
+    ///
+    /// # Returns
+    ///
+    /// Si hay un error al leer la entrada, devuelve un error de tipo `GrepError`.
+    fn starts_binary<R: BufRead>(&self, reader: &mut R) -> Result<bool, GrepError> {
+        if self.terminator == b'\0' {
+            return Ok(false);
+        }
+        match reader.fill_buf() {
+            Ok(block) => Ok(block.contains(&0)),
+            Err(_) => Err(GrepError::ErrArchivo),
+        }
+    }
+
+    /// Devuelve el texto de una línea o registro leído, sin el terminador.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Los bytes leídos, incluyendo el terminador o el separador final si lo hay.
+    ///
+    /// # Returns
+    ///
+    /// Devuelve los bytes tal como se leyeron, aunque no sean UTF-8 válido.
+    fn line_text<'b>(&self, buffer: &'b [u8]) -> &'b [u8] {
+        if let RecordSplit::Separator(separator) = &self.records {
+            return buffer.strip_suffix(separator.as_slice()).unwrap_or(buffer);
+        }
+        buffer.strip_suffix(&[self.terminator]).unwrap_or(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regex_set::RegexSet;
+
+    fn search_bytes(
+        options: &GrepOptions,
+        pattern: &str,
+        input: &[u8],
+    ) -> Result<Vec<u8>, GrepError> {
+        let matcher = Matcher::new(RegexSet::new(&[pattern])?, options);
+        let mut printer = Printer::new(Vec::new(), options);
+        Searcher::new(options)?.search(&mut &input[..], "-", &matcher, &mut printer)?;
+        Ok(printer.into_inner())
+    }
+
+    fn search_with(options: &GrepOptions, pattern: &str, input: &str) -> Result<String, GrepError> {
+        let out = search_bytes(options, pattern, input.as_bytes())?;
+        Ok(String::from_utf8(out).unwrap_or_default())
+    }
+
+    fn search(pattern: &str, input: &str) -> Result<String, GrepError> {
+        search_with(&GrepOptions::default(), pattern, input)
+    }
+
+    #[test]
+    fn test_search_lines() -> Result<(), GrepError> {
+        let out = search("apple|melon", "banana\napple\nmelon\nkiwi")?;
+        assert_eq!(out, "apple\nmelon\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_repeated_lines() -> Result<(), GrepError> {
+        let out = search("apple|pple", "apple\nkiwi\napple\napple")?;
+        assert_eq!(out, "apple\napple\napple\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_unique() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            unique: true,
+            ..GrepOptions::default()
+        };
+        let out = search_with(&options, "apple|kiwi", "apple\nkiwi\napple\napple")?;
+        assert_eq!(out, "apple\nkiwi\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_invert_match() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            invert_match: true,
+            ..GrepOptions::default()
+        };
+        let out = search_with(&options, "apple|melon", "banana\napple\nmelon\nkiwi")?;
+        assert_eq!(out, "banana\nkiwi\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_invert_line_regexp() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            invert_match: true,
+            line_regexp: true,
+            ..GrepOptions::default()
+        };
+        let out = search_with(&options, "apple|melon", "apple\npineapple\nmelon\nkiwi")?;
+        assert_eq!(out, "pineapple\nkiwi\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_line_numbers_and_offsets() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            line_number: true,
+            byte_offset: true,
+            ..GrepOptions::default()
+        };
+        let out = search_with(&options, "melon", "banana\nmelon\r\nkiwi\nwatermelon")?;
+        assert_eq!(out, "2:7:melon\r\n4:19:watermelon\n");
+        Ok(())
+    }
+
+    fn context(before_context: usize, after_context: usize) -> GrepOptions {
+        GrepOptions {
+            before_context,
+            after_context,
+            line_number: true,
+            ..GrepOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_search_after_context() -> Result<(), GrepError> {
+        let input = "a\nx\nb\nc\nd\nx\ne";
+        let out = search_with(&context(0, 1), "x", input)?;
+        assert_eq!(out, "2:x\n3-b\n--\n6:x\n7-e\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_before_context() -> Result<(), GrepError> {
+        let input = "a\nb\nc\nx\nd\nx";
+        let out = search_with(&context(2, 0), "x", input)?;
+        assert_eq!(out, "2-b\n3-c\n4:x\n5-d\n6:x\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_context_merges_windows() -> Result<(), GrepError> {
+        let input = "a\nx\nb\nc\nx\nd\ne\nf\ng\nx";
+        let out = search_with(&context(1, 1), "x", input)?;
+        assert_eq!(out, "1-a\n2:x\n3-b\n4-c\n5:x\n6-d\n--\n9-g\n10:x\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_context_invert() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            invert_match: true,
+            ..context(1, 0)
+        };
+        let out = search_with(&options, "x", "x\nx\na")?;
+        assert_eq!(out, "2-x\n3:a\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_max_count() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            max_count: Some(2),
+            ..GrepOptions::default()
+        };
+        let matcher = Matcher::new(RegexSet::new(&["a"])?, &options);
+        let mut printer = Printer::new(Vec::new(), &options);
+        let mut reader = "banana\nkiwi\nmanzana\npera\nuva\n".as_bytes();
+        let count = Searcher::new(&options)?.search(&mut reader, "-", &matcher, &mut printer)?;
+        assert_eq!(count, 2);
+        assert_eq!(printer.into_inner(), b"banana\nmanzana\n");
+        assert_eq!(reader, b"pera\nuva\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_max_count_trailing_context() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            max_count: Some(1),
+            invert_match: true,
+            ..context(0, 2)
+        };
+        let matcher = Matcher::new(RegexSet::new(&["a"])?, &options);
+        let mut printer = Printer::new(Vec::new(), &options);
+        let mut reader = "banana\nkiwi\nmanzana\nuva\nlimon\n".as_bytes();
+        Searcher::new(&options)?.search(&mut reader, "-", &matcher, &mut printer)?;
+        assert_eq!(printer.into_inner(), b"2:kiwi\n3-manzana\n4-uva\n");
+        assert_eq!(reader, b"limon\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_max_count_zero() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            max_count: Some(0),
+            ..GrepOptions::default()
+        };
+        assert_eq!(count(&options, "a", "banana\n")?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_crlf_and_last_line() -> Result<(), GrepError> {
+        let out = search("kiwi", "banana\r\nkiwi\r\nkiwi")?;
+        assert_eq!(out, "kiwi\r\nkiwi\n");
+        let out = search("^kiwi$", "banana\r\nkiwi\r\nkiwi")?;
+        assert_eq!(out, "kiwi\n");
+        Ok(())
+    }
+
+    fn count(options: &GrepOptions, pattern: &str, input: &str) -> Result<usize, GrepError> {
+        let matcher = Matcher::new(RegexSet::new(&[pattern])?, options);
+        let mut printer = Printer::new(Vec::new(), options);
+        Searcher::new(options)?.search(&mut input.as_bytes(), "-", &matcher, &mut printer)
+    }
+
+    #[test]
+    fn test_search_no_match() -> Result<(), GrepError> {
+        assert_eq!(count(&GrepOptions::default(), "uva", "banana\nkiwi\n")?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_count_reads_everything() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            output_mode: OutputMode::Count,
+            ..GrepOptions::default()
+        };
+        assert_eq!(count(&options, "a", "banana\nkiwi\nmanzana\n")?, 2);
+        assert_eq!(search_with(&options, "a", "banana\nkiwi\nmanzana\n")?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_stops_at_first() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            output_mode: OutputMode::FilesWithMatches,
+            ..GrepOptions::default()
+        };
+        let matcher = Matcher::new(RegexSet::new(&["a"])?, &options);
+        let mut printer = Printer::new(Vec::new(), &options);
+        let mut reader = "banana\nkiwi\nmanzana\n".as_bytes();
+        let count = Searcher::new(&options)?.search(&mut reader, "-", &matcher, &mut printer)?;
+        assert_eq!(count, 1);
+        assert_eq!(reader, b"kiwi\nmanzana\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_null_data() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            null_data: true,
+            ..GrepOptions::default()
+        };
+        let output = search_with(&options, "^b", "banana\nkiwi\0manzana\0bo\r\n")?;
+        assert_eq!(output, "banana\nkiwi\0bo\r\n\0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_record_start() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            record_start: Some("^[[:digit:]]+ ".to_string()),
+            line_number: true,
+            after_context: 1,
+            ..GrepOptions::default()
+        };
+        let input = "1 ok\n2 error\n  at main\n  at run\n3 ok\n4 ok\n5 info\n  error\n";
+        let output = search_with(&options, "error", input)?;
+        assert_eq!(
+            output,
+            "2:2 error\n  at main\n  at run\n5-3 ok\n--\n7:5 info\n  error\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_record_separator() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            record_separator: Some(b"\n\n".to_vec()),
+            ..GrepOptions::default()
+        };
+        let input = "kiwi\nbanana\n\nmanzana\n\nuva\nbanana";
+        let output = search_with(&options, "^kiwi|^uva", input)?;
+        assert_eq!(output, "kiwi\nbanana\n\nuva\nbanana\n\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_multiline() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            multiline: true,
+            line_number: true,
+            ..GrepOptions::default()
+        };
+        let input = "fn foo(\n    bar)\nfn foo(baz)\nfn foo(\n  x)\nbar\n";
+        let output = search_with(&options, "foo\\(\\n\\s*bar", input)?;
+        assert_eq!(output, "1:fn foo(\n2:    bar)\n");
+
+        let output = search_with(&options, "(?s)x.*bar", input)?;
+        assert_eq!(output, "5:  x)\n6:bar\n");
+
+        let output = search_with(&options, "(?m)^bar", input)?;
+        assert_eq!(output, "6:bar\n");
+        assert_eq!(search_with(&options, "^bar", input)?, "");
+
+        let options = GrepOptions {
+            multiline: true,
+            invert_match: true,
+            ..GrepOptions::default()
+        };
+        let output = search_with(&options, "(?m)foo.baz|fn foo.$", input)?;
+        assert_eq!(output, "    bar)\n  x)\nbar\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_file_matches() -> Result<(), GrepError> {
+        let options = GrepOptions::default();
+        let out = search_bytes(&options, "ab", b"x\0y\nab\nab\n")?;
+        assert_eq!(out, b"Binary file - matches\n");
+        assert_eq!(search_bytes(&options, "zz", b"x\0y\nab\n")?, b"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_detected_by_encoding() -> Result<(), GrepError> {
+        let options = GrepOptions::default();
+        let out = search_bytes(&options, "ab", b"ab 1\n\xff\xfe\nab 2\n")?;
+        assert_eq!(out, b"ab 1\nBinary file - matches\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_as_text() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            binary_files: BinaryFiles::Text,
+            ..GrepOptions::default()
+        };
+        let out = search_bytes(&options, "a.", b"x\0y\nab\n\xffa\xe9\n")?;
+        assert_eq!(out, b"ab\n\xffa\xe9\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_without_match() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            binary_files: BinaryFiles::WithoutMatch,
+            output_mode: OutputMode::Count,
+            ..GrepOptions::default()
+        };
+        assert_eq!(count(&options, "ab", "x\0y\nab\n")?, 0);
+        assert_eq!(count(&options, "ab", "ab\nab\n")?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_count() -> Result<(), GrepError> {
+        let options = GrepOptions {
+            output_mode: OutputMode::Count,
+            ..GrepOptions::default()
+        };
+        assert_eq!(count(&options, "ab", "x\0y\nab\nab\n")?, 2);
+        Ok(())
+    }
+}
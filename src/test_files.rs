@@ -0,0 +1,27 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::grep_error::GrepError;
+
+/// Devuelve una ruta en el directorio temporal para los archivos de un test, distinta en cada
+/// proceso.
+pub fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("egrep_rustico_{}_{}", name, std::process::id()))
+}
+
+/// Escribe un archivo, creando los directorios que le falten.
+pub fn write(path: &Path, content: &[u8]) -> Result<(), GrepError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| GrepError::ErrArchivo)?;
+    }
+    fs::write(path, content).map_err(|_| GrepError::ErrArchivo)
+}
+
+/// Escribe un archivo en el directorio temporal y devuelve su ruta.
+pub fn write_temp(name: &str, content: &[u8]) -> Result<String, GrepError> {
+    let path = temp_path(name);
+    write(&path, content)?;
+    Ok(path.to_string_lossy().into_owned())
+}
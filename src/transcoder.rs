@@ -0,0 +1,163 @@
+use std::io::{self, Read};
+
+use crate::encoding::Encoding;
+
+/// Tamaño de los bloques que se leen de la entrada original.
+const BLOCK_SIZE: usize = 8 * 1024;
+
+/// Lector que convierte a UTF-8, a medida que se lee, una entrada en otra codificación.
+///
+/// Las unidades UTF-16 inválidas, como un sustituto sin pareja o un byte suelto al final, se
+/// reemplazan por `U+FFFD`. Con `Utf8` los bytes pasan sin cambios.
+pub struct Transcoder<R: Read> {
+    inner: R,
+    encoding: Encoding,
+    pending: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    finished: bool,
+}
+
+impl<R: Read> Transcoder<R> {
+    /// Crea un lector que convierte `inner` a UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - El lector sobre la entrada original, ya sin la marca de orden de bytes.
+    /// * `encoding` - La codificación de la entrada original.
+    pub fn new(inner: R, encoding: Encoding) -> Transcoder<R> {
+        Transcoder {
+            inner,
+            encoding,
+            pending: Vec::new(),
+            output: Vec::new(),
+            pos: 0,
+            finished: false,
+        }
+    }
+
+    /// Lee el siguiente bloque de la entrada original y lo convierte.
+    fn fill(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.pos = 0;
+
+        let mut block = [0; BLOCK_SIZE];
+        let read = self.inner.read(&mut block)?;
+        self.pending.extend_from_slice(&block[..read]);
+        self.finished = read == 0;
+        self.decode();
+        Ok(())
+    }
+
+    /// Convierte lo pendiente a UTF-8, dejando pendiente lo que puede seguir en el próximo bloque.
+    fn decode(&mut self) {
+        let little_endian = match self.encoding {
+            Encoding::Latin1 => {
+                let text: String = self.pending.iter().map(|&byte| char::from(byte)).collect();
+                self.output.extend_from_slice(text.as_bytes());
+                self.pending.clear();
+                return;
+            }
+            Encoding::Utf16Le => true,
+            Encoding::Utf16Be => false,
+            Encoding::Auto | Encoding::Utf8 => {
+                self.output.append(&mut self.pending);
+                return;
+            }
+        };
+
+        let mut units: Vec<u16> = self
+            .pending
+            .chunks_exact(2)
+            .map(|pair| match little_endian {
+                true => u16::from_le_bytes([pair[0], pair[1]]),
+                false => u16::from_be_bytes([pair[0], pair[1]]),
+            })
+            .collect();
+        let mut used = units.len() * 2;
+        if !self.finished
+            && units
+                .last()
+                .is_some_and(|unit| (0xd800..0xdc00).contains(unit))
+        {
+            units.pop();
+            used -= 2;
+        }
+
+        let mut text: String = char::decode_utf16(units)
+            .map(|decoded| decoded.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        self.pending.drain(..used);
+        if self.finished && !self.pending.is_empty() {
+            text.push(char::REPLACEMENT_CHARACTER);
+            self.pending.clear();
+        }
+        self.output.extend_from_slice(text.as_bytes());
+    }
+}
+
+impl<R: Read> Read for Transcoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.output.len() && !self.finished {
+            self.fill()?;
+        }
+        let available = &self.output[self.pos..];
+        let size = available.len().min(buf.len());
+        buf[..size].copy_from_slice(&available[..size]);
+        self.pos += size;
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcode(input: &[u8], encoding: Encoding) -> String {
+        let mut text = String::new();
+        match Transcoder::new(input, encoding).read_to_string(&mut text) {
+            Ok(_) => text,
+            Err(error) => error.to_string(),
+        }
+    }
+
+    fn utf16(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| match little_endian {
+                true => unit.to_le_bytes(),
+                false => unit.to_be_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_latin1() {
+        assert_eq!(transcode(b"caf\xe9\n", Encoding::Latin1), "café\n");
+    }
+
+    #[test]
+    fn test_utf16() {
+        let text = "año,\u{1f34c}\r\nkiwi\n";
+        assert_eq!(transcode(&utf16(text, true), Encoding::Utf16Le), text);
+        assert_eq!(transcode(&utf16(text, false), Encoding::Utf16Be), text);
+    }
+
+    #[test]
+    fn test_utf16_across_blocks() {
+        let text = "aaa\u{1f34c}b";
+        let bytes = utf16(text, true);
+        let reader = bytes[..8].chain(&bytes[8..]);
+        let mut decoded = String::new();
+        let result = Transcoder::new(reader, Encoding::Utf16Le).read_to_string(&mut decoded);
+        assert!(result.is_ok());
+        assert_eq!(decoded, text);
+    }
+
+    #[test]
+    fn test_utf16_invalid() {
+        assert_eq!(
+            transcode(b"a\x00\x00\xd8b\x00c", Encoding::Utf16Le),
+            "a\u{fffd}b\u{fffd}"
+        );
+    }
+}
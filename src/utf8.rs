@@ -0,0 +1,92 @@
+//! Decodifica de a un caracter bytes que pueden no ser UTF-8 válido.
+//!
+//! Donde los bytes son UTF-8 válido se devuelve el caracter decodificado; donde no, cada byte
+//! suelto se toma como el caracter Latin-1 del mismo valor y ocupa un solo byte.
+
+use std::str;
+
+/// Decodifica el primer caracter de los bytes.
+///
+/// # Arguments
+///
+/// * `bytes` - Los bytes a decodificar.
+///
+/// # Returns
+///
+/// Devuelve el caracter y cuántos bytes ocupa, o `None` si no hay bytes.
+pub fn decode(bytes: &[u8]) -> Option<(char, usize)> {
+    let first = *bytes.first()?;
+    if first.is_ascii() {
+        return Some((char::from(first), 1));
+    }
+    let prefix = &bytes[..bytes.len().min(4)];
+    let valid = match str::from_utf8(prefix) {
+        Ok(valid) => valid,
+        Err(err) => str::from_utf8(&prefix[..err.valid_up_to()]).unwrap_or_default(),
+    };
+    match valid.chars().next() {
+        Some(c) => Some((c, c.len_utf8())),
+        None => Some((char::from(first), 1)),
+    }
+}
+
+/// Decodifica el último caracter de los bytes.
+///
+/// # Arguments
+///
+/// * `bytes` - Los bytes a decodificar.
+///
+/// # Returns
+///
+/// Devuelve el caracter y cuántos bytes ocupa, o `None` si no hay bytes.
+pub fn decode_last(bytes: &[u8]) -> Option<(char, usize)> {
+    let last = *bytes.last()?;
+    for size in 2..=bytes.len().min(4) {
+        let suffix = &bytes[bytes.len() - size..];
+        if let Ok(valid) = str::from_utf8(suffix) {
+            if valid.chars().count() == 1 {
+                return valid.chars().next().map(|c| (c, size));
+            }
+        }
+    }
+    Some((char::from(last), 1))
+}
+
+/// Cuenta los caracteres de los bytes, tomando cada byte inválido como un caracter.
+pub fn count_chars(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    let mut pos = 0;
+    while let Some((_, size)) = decode(&bytes[pos..]) {
+        pos += size;
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"abc"), Some(('a', 1)));
+        assert_eq!(decode("ñu".as_bytes()), Some(('ñ', 2)));
+        assert_eq!(decode(b"\xe9t\xe9"), Some(('é', 1)));
+        assert_eq!(decode(b"\xc3"), Some(('Ã', 1)));
+        assert_eq!(decode(b""), None);
+    }
+
+    #[test]
+    fn test_decode_last() {
+        assert_eq!(decode_last("uñ".as_bytes()), Some(('ñ', 2)));
+        assert_eq!(decode_last(b"t\xe9"), Some(('é', 1)));
+        assert_eq!(decode_last(b"\xa9"), Some(('©', 1)));
+        assert_eq!(decode_last(b""), None);
+    }
+
+    #[test]
+    fn test_count_chars() {
+        assert_eq!(count_chars("ñandú".as_bytes()), 5);
+        assert_eq!(count_chars(b"caf\xe9 \xff"), 6);
+    }
+}
@@ -0,0 +1,18 @@
+use std::{path::PathBuf, vec};
+
+use crate::ignore_rules::IgnoreRules;
+
+/// Representa un directorio que el `InputWalker` está recorriendo.
+#[derive(Debug)]
+pub struct WalkFrame {
+    /// Las rutas que faltan visitar dentro del directorio.
+    pub entries: vec::IntoIter<PathBuf>,
+    /// La profundidad de las rutas de `entries`.
+    pub depth: usize,
+    /// La ruta del directorio tal como se muestra.
+    pub path: PathBuf,
+    /// La ruta con la que se detectan ciclos de enlaces; la canónica si se siguen enlaces.
+    pub dir: PathBuf,
+    /// Las reglas para ignorar que se aplican dentro del directorio, de menor a mayor prioridad.
+    pub ignore: Vec<IgnoreRules>,
+}